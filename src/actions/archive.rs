@@ -1,4 +1,4 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use flate2::write::GzEncoder;
 use flate2::Compression;
 use std::fs::File;
@@ -6,15 +6,16 @@ use std::path::{Path, PathBuf};
 use tar::Builder;
 
 use crate::session::Session;
+use crate::utils::{file_safe, sanitize_file_component};
+
+/// File name used when archiving a single session
+pub fn archive_file_name(session: &Session) -> String {
+    format!("{}_{}.tar.gz", file_safe(&session.project), session.id)
+}
 
 /// Archive a session to a tar.gz file
 pub fn archive_session(session: &Session, output_dir: &Path) -> Result<PathBuf> {
-    let archive_name = format!(
-        "{}_{}.tar.gz",
-        session.project,
-        session.id
-    );
-    let archive_path = output_dir.join(&archive_name);
+    let archive_path = output_dir.join(archive_file_name(session));
 
     let file = File::create(&archive_path)
         .with_context(|| format!("Failed to create archive {:?}", archive_path))?;
@@ -28,7 +29,7 @@ pub fn archive_session(session: &Session, output_dir: &Path) -> Result<PathBuf>
         .with_context(|| format!("Failed to add {:?} to archive", session.path))?;
 
     // Add associated directory if it exists
-    let dir_path = session.path.with_extension("");
+    let dir_path = session.sidecar_dir();
     if dir_path.is_dir() {
         let dir_name = dir_path.file_name().unwrap().to_str().unwrap();
         archive
@@ -41,6 +42,27 @@ pub fn archive_session(session: &Session, output_dir: &Path) -> Result<PathBuf>
     Ok(archive_path)
 }
 
+/// File name for a named batch archive (`.tar.gz` is added unless already present)
+pub fn batch_archive_file_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    let stem = sanitize_file_component(name.strip_suffix(".tar.gz").unwrap_or(name));
+    (!stem.is_empty()).then(|| format!("{}.tar.gz", stem))
+}
+
+/// Archive sessions into one named tar.gz in `output_dir`, refusing to overwrite
+pub fn archive_batch(sessions: &[&Session], output_dir: &Path, name: &str) -> Result<PathBuf> {
+    let Some(file_name) = batch_archive_file_name(name) else {
+        bail!("Archive name '{}' is empty", name);
+    };
+    let archive_path = output_dir.join(file_name);
+    if archive_path.exists() {
+        bail!("{} already exists", archive_path.display());
+    }
+
+    archive_sessions(sessions, &archive_path)?;
+    Ok(archive_path)
+}
+
 /// Archive multiple sessions to a single tar.gz file
 pub fn archive_sessions(sessions: &[&Session], output_path: &Path) -> Result<()> {
     let file = File::create(output_path)
@@ -52,14 +74,15 @@ pub fn archive_sessions(sessions: &[&Session], output_path: &Path) -> Result<()>
         // Create a subdirectory for each project
         let prefix = format!("{}/{}", session.project, session.id);
 
-        // Add the JSONL file
-        let file_name = format!("{}.jsonl", prefix);
+        // Add the JSONL file, keeping a .jsonl.gz suffix
+        let suffix = if session.is_compressed() { "jsonl.gz" } else { "jsonl" };
+        let file_name = format!("{}.{}", prefix, suffix);
         archive
             .append_path_with_name(&session.path, &file_name)
             .with_context(|| format!("Failed to add {:?} to archive", session.path))?;
 
         // Add associated directory if it exists
-        let dir_path = session.path.with_extension("");
+        let dir_path = session.sidecar_dir();
         if dir_path.is_dir() {
             archive
                 .append_dir_all(&prefix, &dir_path)
@@ -83,3 +106,21 @@ pub fn get_default_archive_dir() -> Result<PathBuf> {
 
     Ok(archive_dir)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_archive_file_name() {
+        assert_eq!(
+            batch_archive_file_name("my-app before cleanup").as_deref(),
+            Some("my-app before cleanup.tar.gz")
+        );
+        assert_eq!(
+            batch_archive_file_name(" snap/2026.tar.gz ").as_deref(),
+            Some("snap-2026.tar.gz")
+        );
+        assert_eq!(batch_archive_file_name(" .. "), None);
+    }
+}
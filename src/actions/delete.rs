@@ -1,6 +1,6 @@
 use anyhow::{Context, Result};
+use chrono::Utc;
 use std::fs;
-use std::path::Path;
 
 use crate::session::Session;
 
@@ -11,8 +11,18 @@ pub fn delete_session(session: &Session) -> Result<()> {
         .with_context(|| format!("Failed to delete {:?}", session.path))?;
 
     // Delete associated directory if it exists
-    let dir_path = session.path.with_extension("");
-    if dir_path.is_dir() {
+    let dir_path = session.sidecar_dir();
+    let Ok(metadata) = fs::symlink_metadata(&dir_path) else {
+        return Ok(());
+    };
+    if metadata.file_type().is_symlink() {
+        // Only unlink: the target may live outside the projects tree
+        if dir_path.is_dir() {
+            fs::remove_file(&dir_path)
+                .or_else(|_| fs::remove_dir(&dir_path)) // Windows directory links
+                .with_context(|| format!("Failed to remove link {:?}", dir_path))?;
+        }
+    } else if metadata.is_dir() {
         fs::remove_dir_all(&dir_path)
             .with_context(|| format!("Failed to delete directory {:?}", dir_path))?;
     }
@@ -20,6 +30,15 @@ pub fn delete_session(session: &Session) -> Result<()> {
     Ok(())
 }
 
+/// Whether a session was modified within the last `minutes` (0 disables the check)
+///
+/// Claude Code may still be appending to such a session in another terminal.
+pub fn is_recently_active(session: &Session, minutes: u32) -> bool {
+    minutes > 0
+        && Utc::now().signed_duration_since(session.modified)
+            < chrono::Duration::minutes(minutes as i64)
+}
+
 /// Delete multiple sessions
 pub fn delete_sessions(sessions: &[&Session]) -> Result<usize> {
     let mut deleted = 0;
@@ -31,10 +50,43 @@ pub fn delete_sessions(sessions: &[&Session]) -> Result<usize> {
     Ok(deleted)
 }
 
-/// Check if a path can be deleted (exists and is writable)
-pub fn can_delete(path: &Path) -> bool {
-    path.exists()
-        && fs::metadata(path)
-            .map(|m| !m.permissions().readonly())
-            .unwrap_or(false)
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_recently_active() {
+        let mut session = Session {
+            modified: Utc::now() - chrono::Duration::minutes(30),
+            ..Session::test("abc")
+        };
+        assert!(is_recently_active(&session, 60));
+        assert!(!is_recently_active(&session, 0));
+        session.modified = Utc::now() - chrono::Duration::hours(2);
+        assert!(!is_recently_active(&session, 60));
+    }
+
+    #[test]
+    fn test_delete_unlinks_symlinked_sidecar_dir() {
+        let root = std::env::temp_dir().join(format!("ccsessionctl-symlink-{}", std::process::id()));
+        let target = root.join("elsewhere");
+        fs::create_dir_all(&target).unwrap();
+        fs::write(target.join("keep.txt"), "data").unwrap();
+        let path = root.join("abc.jsonl");
+        fs::write(&path, "{}").unwrap();
+        std::os::unix::fs::symlink(&target, root.join("abc")).unwrap();
+
+        let session = Session {
+            path: path.clone(),
+            size_bytes: 2,
+            ..Session::test("abc")
+        };
+        delete_session(&session).unwrap();
+
+        assert!(!path.exists());
+        assert!(fs::symlink_metadata(root.join("abc")).is_err());
+        assert!(target.join("keep.txt").exists());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
 }
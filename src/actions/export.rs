@@ -1,35 +1,312 @@
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
-use crate::session::{load_session_messages, MessageRole, Session};
+use crate::session::{
+    get_session_preview, load_session_messages, merge_session_messages, MessageRole,
+    RenderOptions, Session,
+};
+use crate::utils::{file_safe, format_time, sanitize_file_component};
 
-/// Export a session to Markdown format
-pub fn export_session_markdown(session: &Session, output_dir: &Path) -> Result<PathBuf> {
-    let messages = load_session_messages(&session.path)?;
+/// Name of the machine-readable export index (merged across exports)
+const INDEX_JSON: &str = "index.json";
+/// Name of the browsable export index (regenerated from index.json)
+const INDEX_MD: &str = "index.md";
 
-    let output_name = format!(
-        "{}_{}.md",
-        session.project,
-        session.id
-    );
-    let output_path = output_dir.join(&output_name);
+/// Export output formats
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    #[default]
+    Markdown,
+    /// Plain text with `User:`/`Assistant:` prefixes and no emoji
+    Text,
+    /// Markdown with YAML front matter for static site generators (Hugo, Jekyll)
+    #[serde(rename = "markdown-frontmatter")]
+    MarkdownFrontmatter,
+}
+
+impl ExportFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Markdown | ExportFormat::MarkdownFrontmatter => "md",
+            ExportFormat::Text => "txt",
+        }
+    }
+}
+
+/// Default export file name template (the extension is added from the format)
+pub const DEFAULT_NAME_TEMPLATE: &str = "{project}_{id}";
+
+/// Placeholders understood by export name templates
+const TEMPLATE_PLACEHOLDERS: &[&str] = &["project", "id", "date", "title"];
+
+/// Longest `{title}` substitution, in characters
+const MAX_TITLE_CHARS: usize = 60;
+
+/// Check that a name template only uses known placeholders
+pub fn validate_name_template(template: &str) -> Result<()> {
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            bail!("Unclosed '{{' in export name template '{}'", template);
+        };
+        let name = &rest[start + 1..start + len];
+        if !TEMPLATE_PLACEHOLDERS.contains(&name) {
+            bail!(
+                "Unknown placeholder '{{{}}}' in export name template (available: {})",
+                name,
+                TEMPLATE_PLACEHOLDERS
+                    .iter()
+                    .map(|p| format!("{{{}}}", p))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+        }
+        rest = &rest[start + len + 1..];
+    }
+    if template.split('/').all(|part| part.trim().is_empty()) {
+        bail!("Export name template '{}' produces an empty file name", template);
+    }
+    Ok(())
+}
+
+/// Relative path used when exporting a session with a name template
+///
+/// Placeholder values are sanitized for the filesystem; `/` in the template itself
+/// creates subdirectories (e.g. `{date}/{title}`).
+pub fn export_file_name(session: &Session, format: ExportFormat, template: &str) -> String {
+    let title = session
+        .custom_title
+        .as_ref()
+        .or(session.summary.as_ref())
+        .or(session.first_message.as_ref())
+        .map(|t| t.chars().take(MAX_TITLE_CHARS).collect::<String>())
+        .unwrap_or_else(|| session.id.clone());
+
+    let name = template
+        .replace("{project}", &sanitize_file_component(&file_safe(&session.project)))
+        .replace("{id}", &sanitize_file_component(&session.id))
+        .replace("{date}", &session.modified.format("%Y-%m-%d").to_string())
+        .replace("{title}", &sanitize_file_component(&title));
+
+    // Rebuild the path from safe components so the template can't escape the export dir
+    let parts: Vec<String> = name
+        .split('/')
+        .map(sanitize_file_component)
+        .filter(|part| !part.is_empty() && part != "." && part != "..")
+        .collect();
+    let stem = if parts.is_empty() {
+        sanitize_file_component(&session.id)
+    } else {
+        parts.join("/")
+    };
+
+    format!("{}.{}", stem, format.extension())
+}
+
+/// Export a session in the given format, naming the file from `template`
+pub fn export_session(
+    session: &Session,
+    output_dir: &Path,
+    format: ExportFormat,
+    options: &RenderOptions,
+    template: &str,
+) -> Result<PathBuf> {
+    let output_path = output_dir.join(export_file_name(session, format, template));
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+
+    match format {
+        ExportFormat::Markdown => export_session_markdown(session, &output_path, options)?,
+        ExportFormat::Text => export_session_text(session, &output_path, options)?,
+        ExportFormat::MarkdownFrontmatter => {
+            std::fs::write(&output_path, render_session(session, format, options)?)
+                .with_context(|| format!("Failed to create {:?}", output_path))?
+        }
+    }
+    Ok(output_path)
+}
+
+/// Render a session in the given format without writing a file
+pub fn render_session(
+    session: &Session,
+    format: ExportFormat,
+    options: &RenderOptions,
+) -> Result<String> {
+    match format {
+        ExportFormat::Markdown => export_session_to_string(session, options),
+        ExportFormat::Text => export_session_text_to_string(session, options),
+        ExportFormat::MarkdownFrontmatter => Ok(format!(
+            "{}{}",
+            front_matter(session, options),
+            export_session_to_string(session, options)?
+        )),
+    }
+}
+
+/// YAML front matter block (`---` fenced) describing a session
+///
+/// The title is the custom title, else the summary, else the first message.
+/// Strings are written JSON-quoted, which YAML reads as double-quoted scalars.
+fn front_matter(session: &Session, options: &RenderOptions) -> String {
+    let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+    let title = session
+        .custom_title
+        .as_ref()
+        .or(session.summary.as_ref())
+        .or(session.first_message.as_ref())
+        .unwrap_or(&session.id);
+    let date = session.created.unwrap_or(session.modified);
+
+    format!(
+        "---\ntitle: {}\ndate: {}\nproject: {}\ntags: [{}, {}]\nsession_id: {}\n---\n\n",
+        quote(title),
+        format_time(date, "%Y-%m-%dT%H:%M:%S%:z", options.local_time),
+        quote(&session.project),
+        quote("claude-code"),
+        quote(&session.project),
+        quote(&session.id)
+    )
+}
+
+/// File name for a merged resume chain: the newest session's name with a `_merged` suffix
+pub fn merged_file_name(sessions: &[&Session], format: ExportFormat, template: &str) -> String {
+    let Some(newest) = sessions.iter().max_by_key(|s| s.modified) else {
+        return format!("merged.{}", format.extension());
+    };
+    let name = export_file_name(newest, format, template);
+    let ext = format.extension();
+    let stem = name.strip_suffix(&format!(".{}", ext)).unwrap_or(&name);
+    format!("{}_merged.{}", stem, ext)
+}
 
-    let mut file = File::create(&output_path)
+/// Render a manually selected resume chain as one deduplicated conversation
+pub fn render_merged_sessions(
+    sessions: &[&Session],
+    format: ExportFormat,
+    options: &RenderOptions,
+) -> Result<String> {
+    let options = RenderOptions {
+        plain: options.plain || format == ExportFormat::Text,
+        ..*options
+    };
+    let mut sessions = sessions.to_vec();
+    sessions.sort_by_key(|s| s.modified);
+
+    let chain = sessions
+        .iter()
+        .map(|s| load_session_messages(&s.path, &options))
+        .collect::<Result<Vec<_>>>()?;
+    let messages = merge_session_messages(chain);
+
+    let ids: Vec<&str> = sessions.iter().map(|s| s.id.as_str()).collect();
+    let projects: Vec<&str> = sessions.iter().fold(Vec::new(), |mut projects, s| {
+        if !projects.contains(&s.project.as_str()) {
+            projects.push(&s.project);
+        }
+        projects
+    });
+    let dates = match (messages.first(), messages.last()) {
+        (Some(first), Some(last)) => format!(
+            "{} to {}",
+            format_time(first.timestamp, "%Y-%m-%d %H:%M", options.local_time),
+            format_time(last.timestamp, "%Y-%m-%d %H:%M %Z", options.local_time)
+        ),
+        _ => "-".to_string(),
+    };
+
+    let mut output = String::new();
+    match format {
+        ExportFormat::Markdown | ExportFormat::MarkdownFrontmatter => {
+            output.push_str(&format!("# Merged session ({} parts)\n\n", ids.len()));
+            output.push_str(&format!("**Project:** {}\n", projects.join(", ")));
+            output.push_str(&format!("**Sessions:** {}\n", ids.join(", ")));
+            output.push_str(&format!("**Dates:** {}\n", dates));
+            output.push_str("\n---\n\n");
+        }
+        ExportFormat::Text => {
+            output.push_str(&format!("Merged session ({} parts)\n", ids.len()));
+            output.push_str(&format!("Project: {}\n", projects.join(", ")));
+            output.push_str(&format!("Sessions: {}\n", ids.join(", ")));
+            output.push_str(&format!("Dates: {}\n\n", dates));
+        }
+    }
+
+    for msg in messages {
+        let role_label = match msg.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            MessageRole::System => "System",
+        };
+        // Full dates, since a chain can span several days
+        let time = format_time(msg.timestamp, "%Y-%m-%d %H:%M:%S", options.local_time);
+        match format {
+            ExportFormat::Markdown | ExportFormat::MarkdownFrontmatter => {
+                output.push_str(&format!("### **{}** ({})\n\n", role_label, time))
+            }
+            ExportFormat::Text => output.push_str(&format!("{} ({}):\n", role_label, time)),
+        }
+        output.push_str(&msg.content);
+        output.push_str("\n\n");
+    }
+
+    Ok(output)
+}
+
+/// Merge a resume chain and write it to one file in the export directory
+pub fn export_merged_sessions(
+    sessions: &[&Session],
+    output_dir: &Path,
+    format: ExportFormat,
+    options: &RenderOptions,
+    template: &str,
+) -> Result<PathBuf> {
+    let content = render_merged_sessions(sessions, format, options)?;
+    let output_path = output_dir.join(merged_file_name(sessions, format, template));
+    if let Some(parent) = output_path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    std::fs::write(&output_path, content)
+        .with_context(|| format!("Failed to create {:?}", output_path))?;
+    Ok(output_path)
+}
+
+/// Export a session to a Markdown file
+pub fn export_session_markdown(
+    session: &Session,
+    output_path: &Path,
+    options: &RenderOptions,
+) -> Result<()> {
+    let messages = load_session_messages(&session.path, options)?;
+
+    let mut file = File::create(output_path)
         .with_context(|| format!("Failed to create {:?}", output_path))?;
 
     // Write header
     writeln!(file, "# Session: {}", session.id)?;
-    writeln!(file, "")?;
+    writeln!(file)?;
     writeln!(file, "**Project:** {}", session.project)?;
-    writeln!(file, "**Date:** {}", session.modified.format("%Y-%m-%d %H:%M:%S UTC"))?;
+    writeln!(
+        file,
+        "**Date:** {}",
+        format_time(session.modified, "%Y-%m-%d %H:%M:%S %Z", options.local_time)
+    )?;
     if let Some(ref summary) = session.summary {
         writeln!(file, "**Summary:** {}", summary)?;
     }
-    writeln!(file, "")?;
+    writeln!(file)?;
     writeln!(file, "---")?;
-    writeln!(file, "")?;
+    writeln!(file)?;
 
     // Write messages
     for msg in messages {
@@ -43,32 +320,154 @@ pub fn export_session_markdown(session: &Session, output_dir: &Path) -> Result<P
             file,
             "### {} ({})",
             role_label,
-            msg.timestamp.format("%H:%M:%S")
+            format_time(msg.timestamp, "%H:%M:%S", options.local_time)
         )?;
-        writeln!(file, "")?;
+        writeln!(file)?;
         writeln!(file, "{}", msg.content)?;
-        writeln!(file, "")?;
+        writeln!(file)?;
     }
 
-    Ok(output_path)
+    Ok(())
 }
 
-/// Export multiple sessions to Markdown files
-pub fn export_sessions_markdown(sessions: &[&Session], output_dir: &Path) -> Result<Vec<PathBuf>> {
-    let mut paths = Vec::new();
+/// Export a session to a plain text file (no emoji or Markdown markers)
+pub fn export_session_text(
+    session: &Session,
+    output_path: &Path,
+    options: &RenderOptions,
+) -> Result<()> {
+    let content = export_session_text_to_string(session, options)?;
 
-    for session in sessions {
-        let path = export_session_markdown(session, output_dir)?;
-        paths.push(path);
+    std::fs::write(output_path, content)
+        .with_context(|| format!("Failed to create {:?}", output_path))?;
+
+    Ok(())
+}
+
+/// Render a session as plain text
+pub fn export_session_text_to_string(session: &Session, options: &RenderOptions) -> Result<String> {
+    let options = RenderOptions {
+        plain: true,
+        ..*options
+    };
+    let messages = load_session_messages(&session.path, &options)?;
+    let mut output = String::new();
+
+    output.push_str(&format!("Session: {}\n", session.id));
+    output.push_str(&format!("Project: {}\n", session.project));
+    output.push_str(&format!(
+        "Date: {}\n",
+        format_time(session.modified, "%Y-%m-%d %H:%M:%S %Z", options.local_time)
+    ));
+    if let Some(ref summary) = session.summary {
+        output.push_str(&format!("Summary: {}\n", summary));
+    }
+    output.push('\n');
+
+    for msg in messages {
+        let role_label = match msg.role {
+            MessageRole::User => "User",
+            MessageRole::Assistant => "Assistant",
+            MessageRole::System => "System",
+        };
+
+        output.push_str(&format!(
+            "{} ({}):\n",
+            role_label,
+            format_time(msg.timestamp, "%H:%M:%S", options.local_time)
+        ));
+        output.push_str(&msg.content);
+        output.push_str("\n\n");
+    }
+
+    Ok(output)
+}
+
+/// One exported file in the export directory index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub file: String,
+    pub project: String,
+    pub id: String,
+    pub date: DateTime<Utc>,
+    pub preview: String,
+}
+
+/// Merge newly exported files into `index.json` and regenerate `index.md`
+///
+/// Entries from earlier exports are kept unless their file has since been removed.
+pub fn update_export_index(output_dir: &Path, exported: &[(&Session, PathBuf)]) -> Result<PathBuf> {
+    let json_path = output_dir.join(INDEX_JSON);
+
+    // Keyed by file name so re-exporting a session replaces its entry
+    let mut entries: BTreeMap<String, IndexEntry> = BTreeMap::new();
+    if json_path.exists() {
+        let content = std::fs::read_to_string(&json_path)
+            .with_context(|| format!("Failed to read {:?}", json_path))?;
+        let existing: Vec<IndexEntry> = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {:?}", json_path))?;
+        entries.extend(existing.into_iter().map(|e| (e.file.clone(), e)));
     }
 
-    Ok(paths)
+    for (session, path) in exported {
+        // Relative to the export dir, since templates may create subdirectories
+        let file = path
+            .strip_prefix(output_dir)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .into_owned();
+        entries.insert(
+            file.clone(),
+            IndexEntry {
+                file,
+                project: session.project.clone(),
+                id: session.id.clone(),
+                date: session.modified,
+                preview: get_session_preview(session),
+            },
+        );
+    }
+
+    let mut entries: Vec<IndexEntry> = entries
+        .into_values()
+        .filter(|e| output_dir.join(&e.file).exists())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.date));
+
+    std::fs::write(&json_path, serde_json::to_string_pretty(&entries)?)
+        .with_context(|| format!("Failed to write {:?}", json_path))?;
+
+    let md_path = output_dir.join(INDEX_MD);
+    let mut md = String::from("# Exported Sessions\n\n");
+    md.push_str("| Date | Project | Session | Preview |\n|---|---|---|---|\n");
+    for entry in &entries {
+        md.push_str(&format!(
+            "| {} | {} | [{}]({}) | {} |\n",
+            entry.date.format("%Y-%m-%d %H:%M"),
+            escape_table_cell(&entry.project),
+            entry.id,
+            entry.file.replace(' ', "%20"),
+            escape_table_cell(&entry.preview)
+        ));
+    }
+    std::fs::write(&md_path, md).with_context(|| format!("Failed to write {:?}", md_path))?;
+
+    Ok(md_path)
+}
+
+/// Make text safe for a single Markdown table cell
+fn escape_table_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace('\n', " ")
 }
 
-/// Get default export directory (~/claude-sessions-export/)
-pub fn get_default_export_dir() -> Result<PathBuf> {
-    let home = dirs::home_dir().context("Could not find home directory")?;
-    let export_dir = home.join("claude-sessions-export");
+/// Get the export directory: `custom` if given, else ~/claude-sessions-export/
+pub fn get_export_dir(custom: Option<&Path>) -> Result<PathBuf> {
+    let export_dir = match custom {
+        Some(dir) => dir.to_path_buf(),
+        None => dirs::home_dir()
+            .context("Could not find home directory")?
+            .join("claude-sessions-export"),
+    };
 
     if !export_dir.exists() {
         std::fs::create_dir_all(&export_dir)?;
@@ -78,15 +477,15 @@ pub fn get_default_export_dir() -> Result<PathBuf> {
 }
 
 /// Export session to a string (for preview)
-pub fn export_session_to_string(session: &Session) -> Result<String> {
-    let messages = load_session_messages(&session.path)?;
+pub fn export_session_to_string(session: &Session, options: &RenderOptions) -> Result<String> {
+    let messages = load_session_messages(&session.path, options)?;
     let mut output = String::new();
 
     output.push_str(&format!("# Session: {}\n\n", session.id));
     output.push_str(&format!("**Project:** {}\n", session.project));
     output.push_str(&format!(
         "**Date:** {}\n",
-        session.modified.format("%Y-%m-%d %H:%M:%S UTC")
+        format_time(session.modified, "%Y-%m-%d %H:%M:%S %Z", options.local_time)
     ));
     if let Some(ref summary) = session.summary {
         output.push_str(&format!("**Summary:** {}\n", summary));
@@ -103,7 +502,7 @@ pub fn export_session_to_string(session: &Session) -> Result<String> {
         output.push_str(&format!(
             "### {} ({})\n\n",
             role_label,
-            msg.timestamp.format("%H:%M:%S")
+            format_time(msg.timestamp, "%H:%M:%S", options.local_time)
         ));
         output.push_str(&msg.content);
         output.push_str("\n\n");
@@ -111,3 +510,160 @@ pub fn export_session_to_string(session: &Session) -> Result<String> {
 
     Ok(output)
 }
+
+/// One row of `--export-csv`
+#[derive(Serialize)]
+struct CsvRow<'a> {
+    project: &'a str,
+    id: &'a str,
+    modified: String,
+    size_bytes: u64,
+    message_count: Option<usize>,
+    token_count: Option<usize>,
+    /// Model with the most assistant tokens
+    model: Option<&'a str>,
+}
+
+/// Write one CSV row of metadata per session (load metadata first for counts and model)
+pub fn write_sessions_csv(sessions: &[Session], writer: impl Write) -> Result<()> {
+    let mut csv = csv::Writer::from_writer(writer);
+    for session in sessions {
+        let model = session
+            .model_tokens
+            .iter()
+            .max_by_key(|(_, tokens)| **tokens)
+            .map(|(model, _)| model.as_str());
+        csv.serialize(CsvRow {
+            project: &session.project,
+            id: &session.id,
+            modified: session.modified.to_rfc3339(),
+            size_bytes: session.size_bytes,
+            message_count: session.message_count,
+            token_count: session.token_count,
+            model,
+        })?;
+    }
+    csv.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_sessions_csv_quotes_fields() {
+        let mut session = Session::test("abc");
+        session.project = "a, \"quoted\" project".to_string();
+        session.modified = DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        session.message_count = Some(3);
+        session.model_tokens.insert("claude-haiku".to_string(), 10);
+        session.model_tokens.insert("claude-opus".to_string(), 50);
+
+        let mut out = Vec::new();
+        write_sessions_csv(&[session, Session::test("def")], &mut out).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(
+            lines[0],
+            "project,id,modified,size_bytes,message_count,token_count,model"
+        );
+        assert_eq!(
+            lines[1],
+            "\"a, \"\"quoted\"\" project\",abc,2026-01-02T03:04:05+00:00,0,3,,claude-opus"
+        );
+        assert!(lines[2].starts_with("proj,def,"));
+        assert!(lines[2].ends_with(",0,,,"));
+    }
+
+    #[test]
+    fn test_export_file_name_default_template() {
+        let session = Session::test("abc");
+        assert_eq!(
+            export_file_name(&session, ExportFormat::Markdown, DEFAULT_NAME_TEMPLATE),
+            "proj_abc.md"
+        );
+    }
+
+    #[test]
+    fn test_export_file_name_sanitizes_placeholders() {
+        let mut session = Session::test("abc");
+        session.project = "a/proj".to_string();
+        session.custom_title = Some("Fix: the <parser>?".to_string());
+
+        let date = session.modified.format("%Y-%m-%d").to_string();
+        assert_eq!(
+            export_file_name(&session, ExportFormat::Text, "{date}/{project} {title}"),
+            format!("{}/a-proj Fix- the -parser--.txt", date)
+        );
+        assert_eq!(
+            export_file_name(&session, ExportFormat::Markdown, "../{id}"),
+            "abc.md"
+        );
+    }
+
+    #[test]
+    fn test_front_matter_quotes_title_and_tags() {
+        let mut session = Session::test("abc");
+        session.summary = Some("Fix the \"parser\": edge cases".to_string());
+        session.created = Some(
+            chrono::DateTime::parse_from_rfc3339("2026-01-02T03:04:05Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+
+        assert_eq!(
+            front_matter(&session, &RenderOptions::default()),
+            "---\ntitle: \"Fix the \\\"parser\\\": edge cases\"\ndate: 2026-01-02T03:04:05+00:00\n\
+             project: \"proj\"\ntags: [\"claude-code\", \"proj\"]\nsession_id: \"abc\"\n---\n\n"
+        );
+        assert_eq!(
+            ExportFormat::MarkdownFrontmatter.extension(),
+            ExportFormat::Markdown.extension()
+        );
+    }
+
+    #[test]
+    fn test_merged_file_name_uses_newest_session() {
+        let mut old = Session::test("old");
+        old.modified = Utc::now() - chrono::Duration::days(1);
+        let new = Session::test("new");
+        assert_eq!(
+            merged_file_name(&[&new, &old], ExportFormat::Markdown, DEFAULT_NAME_TEMPLATE),
+            "proj_new_merged.md"
+        );
+    }
+
+    #[test]
+    fn test_validate_name_template() {
+        assert!(validate_name_template("{date}/{project}_{title}").is_ok());
+        assert!(validate_name_template("{nope}").is_err());
+        assert!(validate_name_template("{id").is_err());
+        assert!(validate_name_template("/").is_err());
+    }
+
+    #[test]
+    fn test_export_index_merges_with_previous_exports() {
+        let dir = std::env::temp_dir().join(format!("ccsessionctl-index-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let (a, b) = (Session::test("a"), Session::test("b"));
+        let (path_a, path_b) = (dir.join("proj_a.md"), dir.join("proj_b.md"));
+        std::fs::write(&path_a, "a").unwrap();
+        std::fs::write(&path_b, "b").unwrap();
+
+        update_export_index(&dir, &[(&a, path_a)]).unwrap();
+        update_export_index(&dir, &[(&b, path_b)]).unwrap();
+
+        let entries: Vec<IndexEntry> =
+            serde_json::from_str(&std::fs::read_to_string(dir.join(INDEX_JSON)).unwrap()).unwrap();
+        let mut ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        ids.sort();
+        assert_eq!(ids, vec!["a", "b"]);
+        assert!(std::fs::read_to_string(dir.join(INDEX_MD)).unwrap().contains("[a](proj_a.md)"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
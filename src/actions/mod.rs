@@ -2,9 +2,10 @@ pub mod archive;
 pub mod delete;
 pub mod export;
 
-pub use archive::{archive_session, archive_sessions, get_default_archive_dir};
-pub use delete::{delete_session, delete_sessions};
+pub use archive::{archive_batch, archive_file_name, archive_session, get_default_archive_dir};
+pub use delete::{delete_session, delete_sessions, is_recently_active};
 pub use export::{
-    export_session_markdown, export_session_to_string, export_sessions_markdown,
-    get_default_export_dir,
+    export_file_name, export_merged_sessions, export_session, export_session_to_string,
+    get_export_dir, merged_file_name, render_session, update_export_index, validate_name_template,
+    write_sessions_csv, ExportFormat, DEFAULT_NAME_TEMPLATE,
 };
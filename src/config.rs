@@ -0,0 +1,287 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::actions::ExportFormat;
+use crate::session::DEFAULT_CHARS_PER_TOKEN;
+use crate::ui::{ColorTheme, Column, SortField};
+
+/// Persistent defaults loaded from `~/.config/ccsessionctl/config.toml`
+///
+/// Precedence (highest first):
+/// 1. CLI flags given on the command line
+/// 2. Values from the config file
+/// 3. Built-in defaults (`Config::default()`)
+///
+/// Boolean flags can only be switched on from the CLI, so `--relative-time`
+/// wins over `relative_time = false` but cannot turn off `relative_time = true`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Syntax highlighting theme for code blocks
+    pub theme: Option<String>,
+    /// Format used by the TUI export action
+    pub export_format: ExportFormat,
+    /// Directory for TUI exports (default: ~/claude-sessions-export)
+    pub export_dir: Option<PathBuf>,
+    /// Export file name template ({project}, {id}, {date}, {title}; `/` makes folders)
+    pub export_name_template: Option<String>,
+    /// Age threshold for "delete older than" in the TUI
+    pub older_than_days: u32,
+    /// Window for the TUI's recently active projects filter (`f`)
+    pub active_days: u32,
+    /// Refuse to delete sessions modified this recently without `--force` (0 disables)
+    pub protect_recent_minutes: u32,
+    /// `--auto-prune`: delete empty sessions at least this many days old (unset: never)
+    pub prune_empty_after_days: Option<u32>,
+    /// `--auto-prune`: archive sessions at least this many days old, then remove them
+    /// (unset: never)
+    pub archive_after_days: Option<u32>,
+    /// Override for the Claude Code projects directory
+    pub projects_dir: Option<PathBuf>,
+    /// Show recent dates as "3h ago"
+    pub relative_time: bool,
+    /// Show dates and message times in the local time zone instead of UTC (toggle with `U`)
+    pub local_time: bool,
+    /// ASCII markers instead of emoji and Unicode symbols
+    pub ascii: bool,
+    /// Default sort field
+    pub sort: SortField,
+    /// Reverse the default sort order
+    pub reverse: bool,
+    /// Wrap long lines in the preview (toggle with `w`)
+    pub preview_wrap: bool,
+    /// Style Markdown in the preview (toggle with `m`)
+    pub preview_markdown: bool,
+    /// Keep the most recently modified session at the top of the list (toggle with `F`)
+    pub float_active_session: bool,
+    /// TUI color preset: default, high-contrast or monochrome (cycle with `T`)
+    pub color_theme: ColorTheme,
+    /// Session table columns in display order (default: project, date, age, size, msgs, tokens, preview)
+    pub columns: Option<Vec<Column>>,
+    /// Terminal used by `R` to resume a session in a new window, e.g. "alacritty -e"
+    /// (falls back to `$TERMINAL -e`; without either, `R` quits and runs claude here)
+    pub terminal: Option<String>,
+    /// USD per million tokens; shows an estimated cost in the TUI footer when set
+    pub price_per_million_tokens: Option<f64>,
+    /// Characters per token for token estimates (lower for code-heavy sessions)
+    pub chars_per_token: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            export_format: ExportFormat::Markdown,
+            export_dir: None,
+            export_name_template: None,
+            older_than_days: 30,
+            active_days: 14,
+            protect_recent_minutes: 60,
+            prune_empty_after_days: None,
+            archive_after_days: None,
+            projects_dir: None,
+            relative_time: false,
+            local_time: false,
+            ascii: false,
+            sort: SortField::Date,
+            reverse: false,
+            preview_wrap: true,
+            preview_markdown: true,
+            float_active_session: true,
+            color_theme: ColorTheme::Default,
+            columns: None,
+            terminal: None,
+            price_per_million_tokens: None,
+            chars_per_token: DEFAULT_CHARS_PER_TOKEN,
+        }
+    }
+}
+
+impl Config {
+    /// Terminal command prefix for resuming in a new window (config, then `$TERMINAL -e`)
+    pub fn terminal_command(&self) -> Option<String> {
+        self.terminal.clone().or_else(|| {
+            std::env::var("TERMINAL")
+                .ok()
+                .filter(|t| !t.trim().is_empty())
+                .map(|t| format!("{} -e", t))
+        })
+    }
+
+    /// Export directory override with `~` expanded
+    pub fn export_dir(&self) -> Option<PathBuf> {
+        self.export_dir.as_deref().map(expand_tilde)
+    }
+
+    /// Resolved projects directory (config override or ~/.claude/projects)
+    pub fn projects_dir(&self) -> Result<PathBuf> {
+        match self.projects_dir {
+            Some(ref dir) => Ok(expand_tilde(dir)),
+            None => crate::session::default_projects_dir(),
+        }
+    }
+}
+
+/// Get the config file path (~/.config/ccsessionctl/config.toml)
+pub fn get_config_path() -> Result<PathBuf> {
+    let config_dir = dirs::config_dir().context("Could not find config directory")?;
+    Ok(config_dir.join("ccsessionctl").join("config.toml"))
+}
+
+/// Load the config file, creating it with defaults on first run
+pub fn load_config() -> Result<Config> {
+    let path = get_config_path()?;
+
+    if !path.exists() {
+        let config = Config::default();
+        // Best effort: a read-only config dir shouldn't stop the tool
+        let _ = write_config(&config);
+        return Ok(config);
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))
+}
+
+/// Write the config file
+pub fn write_config(config: &Config) -> Result<()> {
+    let path = get_config_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let content = format!(
+        "# ccsessionctl configuration\n\
+         # CLI flags override these values.\n\
+         #\n\
+         # theme = \"base16-ocean.dark\"\n\
+         # projects_dir = \"~/.claude/projects\"\n\
+         # export_dir = \"~/claude-sessions-export\"\n\
+         # export_name_template = \"{{date}}/{{project}}_{{title}}\"\n\
+         # price_per_million_tokens = 3.0\n\
+         # terminal = \"alacritty -e\"\n\
+         # columns = [\"project\", \"date\", \"msgs\", \"tokens\", \"branch\", \"preview\"]\n\
+         # prune_empty_after_days = 7   # --auto-prune rules\n\
+         # archive_after_days = 180\n\n{}",
+        toml::to_string_pretty(config)?
+    );
+    fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
+
+/// TUI view settings remembered between runs (sort and project filter)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ViewState {
+    pub sort: SortField,
+    pub reverse: bool,
+    /// Project filter by name; `None` means "All"
+    pub project: Option<String>,
+}
+
+/// Get the view state file path (~/.config/ccsessionctl/state.toml)
+fn get_view_state_path() -> Result<PathBuf> {
+    Ok(get_config_path()?.with_file_name("state.toml"))
+}
+
+/// Load the view state saved on the last quit (`None` if there isn't one)
+pub fn load_view_state() -> Result<Option<ViewState>> {
+    let path = get_view_state_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let state = toml::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))?;
+    Ok(Some(state))
+}
+
+/// Save the view state
+pub fn save_view_state(state: &ViewState) -> Result<()> {
+    let path = get_view_state_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, toml::to_string_pretty(state)?)
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
+
+/// Get the last-run marker path (~/.config/ccsessionctl/last-run)
+fn get_last_run_path() -> Result<PathBuf> {
+    Ok(get_config_path()?.with_file_name("last-run"))
+}
+
+/// Time recorded by the last `--mark-run` (`None` if there isn't one)
+pub fn load_last_run() -> Result<Option<DateTime<Utc>>> {
+    let path = get_last_run_path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let time = DateTime::parse_from_rfc3339(content.trim())
+        .with_context(|| format!("Failed to parse {:?}", path))?;
+    Ok(Some(time.with_timezone(&Utc)))
+}
+
+/// Record the time of this run for `--since-last-run`
+pub fn save_last_run(time: DateTime<Utc>) -> Result<()> {
+    let path = get_last_run_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(&path, format!("{}\n", time.to_rfc3339()))
+        .with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_tilde(path: &std::path::Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_config_uses_defaults() {
+        let config: Config =
+            toml::from_str("theme = \"base16-ocean.light\"\nsort = \"size\"").unwrap();
+        assert_eq!(config.theme.as_deref(), Some("base16-ocean.light"));
+        assert_eq!(config.sort, SortField::Size);
+        assert_eq!(config.older_than_days, 30);
+        assert_eq!(config.export_format, ExportFormat::Markdown);
+    }
+
+    #[test]
+    fn test_view_state_round_trips() {
+        let state = ViewState {
+            sort: SortField::Name,
+            reverse: true,
+            project: Some("my-app".to_string()),
+        };
+        let text = toml::to_string_pretty(&state).unwrap();
+        assert_eq!(toml::from_str::<ViewState>(&text).unwrap(), state);
+    }
+
+    #[test]
+    fn test_default_config_round_trips() {
+        let text = toml::to_string_pretty(&Config::default()).unwrap();
+        let config: Config = toml::from_str(&text).unwrap();
+        assert_eq!(config.sort, SortField::Date);
+        assert!(!config.relative_time);
+    }
+}
@@ -1,20 +1,39 @@
 mod actions;
+mod config;
 mod session;
 mod ui;
+mod utils;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
 use clap::{Parser, ValueEnum};
+use serde::Serialize;
 use crossterm::{
     event::{DisableMouseCapture, EnableMouseCapture},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use std::io::{self, Write};
+use std::io::{self, IsTerminal, Write};
+use std::path::PathBuf;
 
-use actions::delete_session;
-use session::{get_session_preview, load_session_metadata, scan_sessions};
-use ui::{App, UiState};
+use actions::{
+    delete_session, delete_sessions, is_recently_active, render_session, validate_name_template,
+    ExportFormat,
+};
+use session::{
+    find_duplicate_sessions, get_session_preview, load_session_messages, load_session_metadata,
+    resolve_session, scan_sessions, user_message_lines, SearchIndex, validate_session_file, MessageRole,
+    RenderOptions, Session, DEFAULT_CHARS_PER_TOKEN,
+};
+use config::Config;
+use ui::{App, Column, Highlighter, SortField, UiState};
+use utils::{
+    find_snippet, format_age, format_relative_time, format_time, format_tokens, ColorMode, Colors,
+};
+
+/// Sessions at least this large are highlighted in colored `--list` output
+const LARGE_SESSION_BYTES: u64 = 1024 * 1024;
 
 /// Handle broken pipe errors gracefully (e.g., when piping to head)
 fn writeln_safe(s: &str) -> bool {
@@ -24,6 +43,30 @@ fn writeln_safe(s: &str) -> bool {
     true
 }
 
+/// Report and skip a delete target modified within the protection window
+fn skip_recent(session: &Session, minutes: u32) -> bool {
+    if !is_recently_active(session, minutes) {
+        return false;
+    }
+    eprintln!(
+        "Skipping {} / {}: modified {} ago, may still be in use (--force to include)",
+        session.project,
+        session.id,
+        format_age(session.modified, chrono::Utc::now())
+    );
+    true
+}
+
+/// Format line numbers for a report, e.g. "3, 7, 12, ..."
+fn format_line_numbers(lines: &[usize]) -> String {
+    let shown: Vec<String> = lines.iter().take(5).map(|n| n.to_string()).collect();
+    if lines.len() > 5 {
+        format!("{}, ...", shown.join(", "))
+    } else {
+        shown.join(", ")
+    }
+}
+
 /// Truncate project name for display
 fn truncate_project(s: &str, max: usize) -> String {
     if s.len() <= max {
@@ -33,24 +76,128 @@ fn truncate_project(s: &str, max: usize) -> String {
     }
 }
 
-/// Format token count with K/M suffix
-fn format_tokens(tokens: usize) -> String {
-    if tokens >= 1_000_000 {
-        format!("{:.1}M", tokens as f64 / 1_000_000.0)
-    } else if tokens >= 1_000 {
-        format!("{:.1}K", tokens as f64 / 1_000.0)
-    } else {
-        tokens.to_string()
+#[derive(Debug, Clone, Copy, ValueEnum, Default)]
+enum StatsGroup {
+    #[default]
+    Project,
+    Model,
+    Day,
+    /// ISO week (e.g. 2025-W03)
+    Week,
+    Month,
+}
+
+impl StatsGroup {
+    /// Period a session falls into by modification date (None for non-time groupings)
+    fn period_key(self, modified: chrono::DateTime<chrono::Utc>) -> Option<String> {
+        let format = match self {
+            StatsGroup::Day => "%Y-%m-%d",
+            StatsGroup::Week => "%G-W%V",
+            StatsGroup::Month => "%Y-%m",
+            StatsGroup::Project | StatsGroup::Model => return None,
+        };
+        Some(modified.format(format).to_string())
+    }
+
+    /// Header of the first `--stats` column
+    fn label(self) -> &'static str {
+        match self {
+            StatsGroup::Project => "Project",
+            StatsGroup::Model => "Model",
+            StatsGroup::Day => "Day",
+            StatsGroup::Week => "Week",
+            StatsGroup::Month => "Month",
+        }
+    }
+}
+
+/// Totals for one row of the `--stats` table
+#[derive(Default)]
+struct GroupStats {
+    count: usize,
+    size: u64,
+    dir_size: u64,
+    tokens: usize,
+    attachments: usize,
+    /// Earliest and latest session modification dates
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+}
+
+impl GroupStats {
+    /// Widen the first/last date range to include `modified`
+    fn add_date(&mut self, modified: DateTime<Utc>) {
+        self.first = Some(self.first.map_or(modified, |d| d.min(modified)));
+        self.last = Some(self.last.map_or(modified, |d| d.max(modified)));
     }
 }
 
+/// One row (or the totals) of `--stats --json`; the key field matches the grouping
+#[derive(Serialize)]
+struct StatsRow<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    project: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    period: Option<&'a str>,
+    sessions: usize,
+    size_bytes: u64,
+    dir_size_bytes: u64,
+    tokens: usize,
+    attachments: usize,
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+}
+
+/// One row (or the totals) of `--stats-by model --json`
+#[derive(Serialize)]
+struct ModelStatsRow<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    model: Option<&'a str>,
+    sessions: usize,
+    tokens: usize,
+}
+
+/// `--stats --json` document
+#[derive(Serialize)]
+struct StatsReport<T> {
+    rows: Vec<T>,
+    totals: T,
+}
+
+/// What to do with the sessions picked by --select-matching
 #[derive(Debug, Clone, Copy, ValueEnum, Default)]
-enum SortField {
+enum BulkAction {
+    /// Print the matching sessions
     #[default]
-    Date,
-    Size,
+    List,
+    /// Delete the matching sessions (prompts; -y to skip)
+    Delete,
+    /// Archive each matching session to ~/claude-sessions-archive (or all into one
+    /// file with --archive-name)
+    Archive,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum CountGroup {
     Project,
-    Name,
+}
+
+/// Parse a positive chars-per-token ratio
+fn parse_chars_per_token(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(n) if n.is_finite() && n > 0.0 => Ok(n),
+        _ => Err(format!("expected a positive number, got '{}'", s)),
+    }
+}
+
+/// Parse a size argument such as 500K or 1M
+fn parse_size_arg(s: &str) -> Result<u64, String> {
+    utils::parse_size(s).ok_or_else(|| format!("expected a size like 500K, 1M or 2G, got '{}'", s))
+}
+
+/// Parse a YYYY-MM-DD date argument
+fn parse_date(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d").map_err(|_| format!("expected YYYY-MM-DD, got '{}'", s))
 }
 
 #[derive(Parser)]
@@ -64,40 +211,359 @@ struct Cli {
     #[arg(long)]
     list: bool,
 
+    /// Print only session ids, one per line (for shell integration)
+    #[arg(long)]
+    ids: bool,
+
+    /// Separate ids with NUL instead of newline (for xargs -0); implies --ids
+    #[arg(long)]
+    print0: bool,
+
+    /// Parse every line of every session and report malformed or unrecognized records
+    #[arg(long)]
+    validate: bool,
+
+    /// Delete the search index and rebuild it by reparsing every session
+    #[arg(long)]
+    rebuild_cache: bool,
+
     /// Show session count only
     #[arg(long)]
     count: bool,
 
+    /// Check the environment: projects dir, session totals, search index, clipboard tool,
+    /// editor and pager
+    #[arg(long)]
+    doctor: bool,
+
+    /// List each project's decoded path and session count, tab-separated (no metadata
+    /// loading); most sessions first unless --sort is given
+    #[arg(long)]
+    projects: bool,
+
+    /// Show session counts per group as tab-separated lines (no metadata loading)
+    #[arg(long, value_enum)]
+    count_by: Option<CountGroup>,
+
+    /// Print at most N sessions (with --list or --ids), after sorting and filtering
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+
+    /// Skip the first N sessions (with --list or --ids)
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    offset: usize,
+
     /// Delete all sessions without a name/summary
     #[arg(long)]
     prune_empty: bool,
 
-    /// Preview what would be deleted (use with --prune-empty)
+    /// Delete sessions whose user messages are a prefix of a longer session in the
+    /// same project (forked/resumed duplicates); the longest variant is always kept
+    #[arg(long)]
+    prune_duplicates: bool,
+
+    /// Run the maintenance rules from the config (prune_empty_after_days,
+    /// archive_after_days) without prompting; preview with --dry-run
+    #[arg(long)]
+    auto_prune: bool,
+
+    /// Preview what would be deleted (use with --prune-empty, --prune-duplicates, --auto-prune
+    /// or --delete)
     #[arg(long)]
     dry_run: bool,
 
-    /// Sort by field (date, size, project, name)
-    #[arg(long, short, value_enum, default_value_t = SortField::Date)]
-    sort: SortField,
+    /// Delete a session by id or unique id prefix
+    #[arg(long, value_name = "SESSION_ID_OR_PREFIX")]
+    delete: Option<String>,
+
+    /// Open a session (by id or unique id prefix) straight in the preview pager; q quits
+    #[arg(long, value_name = "SESSION_ID_OR_PREFIX")]
+    view: Option<String>,
+
+    /// Print a session (by id or unique id prefix) to stdout in the export format
+    #[arg(long, value_name = "SESSION_ID_OR_PREFIX")]
+    cat: Option<String>,
+
+    /// Select every session whose content matches QUERY (like TUI search) for --bulk
+    #[arg(long, value_name = "QUERY")]
+    select_matching: Option<String>,
+
+    /// Action for --select-matching (list, delete, archive) [default: list]
+    #[arg(long, value_enum, requires = "select_matching")]
+    bulk: Option<BulkAction>,
+
+    /// With --bulk archive: put all matching sessions into one NAME.tar.gz
+    #[arg(long, value_name = "NAME", requires = "select_matching")]
+    archive_name: Option<String>,
+
+    /// Skip the confirmation prompt (use with --delete, --prune-duplicates or --bulk delete)
+    #[arg(long, short)]
+    yes: bool,
+
+    /// Also delete sessions modified within `protect_recent_minutes` (default 60),
+    /// which may still be in use
+    #[arg(long)]
+    force: bool,
+
+    /// Sort by field (date, size, project, name, created) [default: date]
+    #[arg(long, short, value_enum)]
+    sort: Option<SortField>,
 
     /// Reverse sort order
     #[arg(long, short)]
     reverse: bool,
 
+    /// Oldest sessions first (same as --sort date --reverse)
+    #[arg(long, conflicts_with_all = ["sort", "reverse", "newest"])]
+    oldest: bool,
+
+    /// Newest sessions first (same as --sort date, ignoring `reverse` in the config)
+    #[arg(long, conflicts_with_all = ["sort", "reverse"])]
+    newest: bool,
+
     /// Filter by project name (case-insensitive substring match)
     #[arg(long, short)]
     project: Option<String>,
 
+    /// Skip symlinked project directories and session files when scanning
+    #[arg(long)]
+    no_follow_symlinks: bool,
+
+    /// Include agent sub-sessions (agent-*), which are hidden by default
+    #[arg(long)]
+    include_agents: bool,
+
+    /// Only include sessions modified on or after this date (YYYY-MM-DD, UTC)
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    since: Option<NaiveDate>,
+
+    /// Only include sessions modified on or before this date (YYYY-MM-DD, UTC)
+    #[arg(long, value_name = "DATE", value_parser = parse_date)]
+    until: Option<NaiveDate>,
+
+    /// Only include sessions at least this large (e.g. 500K, 1M; binary units)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size_arg)]
+    min_size: Option<u64>,
+
+    /// Only include sessions at most this large (e.g. 10M)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size_arg)]
+    max_size: Option<u64>,
+
+    /// Only include sessions modified since the last --mark-run (lists them unless
+    /// another output such as --ids or --count is chosen)
+    #[arg(long)]
+    since_last_run: bool,
+
+    /// With --since-last-run: record this run so the next one starts from now
+    #[arg(long, requires = "since_last_run")]
+    mark_run: bool,
+
+    /// Search full session content (case-insensitive) and print matches with context
+    #[arg(long, value_name = "QUERY")]
+    search: Option<String>,
+
+    /// Print sessions whose messages match TERM, with the first matching line
+    #[arg(long, value_name = "TERM")]
+    grep: Option<String>,
+
+    /// Show dates relative to now ("3h ago") for recent sessions
+    #[arg(long)]
+    relative_time: bool,
+
+    /// Show dates and message times in the local time zone instead of UTC
+    #[arg(long)]
+    local_time: bool,
+
+    /// Use ASCII markers instead of emoji and Unicode symbols (for limited fonts and screen
+    /// readers)
+    #[arg(long, alias = "no-unicode")]
+    ascii: bool,
+
+    /// Format used for TUI exports and --cat (markdown, text, markdown-frontmatter)
+    /// [default: markdown]
+    #[arg(long, value_enum)]
+    export_format: Option<ExportFormat>,
+
+    /// Directory for TUI exports [default: ~/claude-sessions-export]
+    #[arg(long, value_name = "PATH")]
+    export_dir: Option<PathBuf>,
+
+    /// Export file name template: {project}, {id}, {date}, {title}; `/` creates folders
+    /// [default: {project}_{id}]
+    #[arg(long, value_name = "TEMPLATE")]
+    export_name_template: Option<String>,
+
+    /// Include thinking blocks in preview and exports
+    #[arg(long)]
+    include_thinking: bool,
+
+    /// Strip tool calls and tool results from preview and exports
+    #[arg(long)]
+    no_tools: bool,
+
+    /// Keep ANSI escape codes (terminal colors) in tool output instead of removing them
+    #[arg(long)]
+    keep_ansi: bool,
+
+    /// Syntax highlighting theme for code blocks in preview
+    #[arg(long, value_name = "NAME")]
+    theme: Option<String>,
+
+    /// Keep the TUI list up to date as session files are created, changed or deleted
+    #[arg(long)]
+    watch: bool,
+
+    /// TUI table columns in order, comma-separated
+    /// (project, date, age, size, msgs, tokens, branch, id, preview)
+    #[arg(long, value_enum, value_delimiter = ',', value_name = "SPEC")]
+    columns: Option<Vec<Column>>,
+
+    /// Characters per token for token estimates (counts are estimates) [default: 4]
+    #[arg(long, value_name = "N", value_parser = parse_chars_per_token)]
+    chars_per_token: Option<f64>,
+
+    /// Color non-TUI output (auto, always, never); auto respects NO_COLOR
+    #[arg(long, value_enum, default_value_t = ColorMode::Auto, value_name = "WHEN")]
+    color: ColorMode,
+
+    /// Write one CSV row of metadata per session to FILE (`-` for stdout)
+    #[arg(long, value_name = "FILE")]
+    export_csv: Option<PathBuf>,
+
     /// Show usage statistics by project
     #[arg(long)]
     stats: bool,
+
+    /// Group statistics by field (project, model) or period (day, week, month); implies --stats
+    #[arg(long, value_enum)]
+    stats_by: Option<StatsGroup>,
+
+    /// Print statistics as JSON rows plus totals (with --stats or --stats-by)
+    #[arg(long)]
+    json: bool,
+}
+
+impl Cli {
+    /// How message content is rendered, so `--cat`, `--grep` and the TUI show the same text
+    fn render_options(&self) -> RenderOptions {
+        RenderOptions {
+            thinking: self.include_thinking,
+            tools: !self.no_tools,
+            strip_ansi: !self.keep_ansi,
+            local_time: self.local_time,
+            ascii: self.ascii,
+            ..RenderOptions::default()
+        }
+    }
+
+    /// Fill in anything not given on the command line from the config file.
+    /// CLI flags always win; boolean flags can only be switched on here.
+    fn apply_config(&mut self, config: &Config) {
+        self.sort = self.sort.or(Some(config.sort));
+        self.export_format = self.export_format.or(Some(config.export_format));
+        if !self.newest {
+            self.reverse |= config.reverse;
+        }
+        self.relative_time |= config.relative_time;
+        self.local_time |= config.local_time;
+        self.ascii |= config.ascii;
+        if self.theme.is_none() {
+            self.theme = config.theme.clone();
+        }
+        if self.export_dir.is_none() {
+            self.export_dir = config.export_dir();
+        }
+        if self.export_name_template.is_none() {
+            self.export_name_template = config.export_name_template.clone();
+        }
+        if self.columns.is_none() {
+            self.columns = config.columns.clone();
+        }
+        if self.chars_per_token.is_none() {
+            self.chars_per_token = Some(config.chars_per_token);
+        }
+    }
 }
 
 fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    // Taken before scanning so sessions written during this run show up next time
+    let started = Utc::now();
+    cli.list |= cli.since_last_run;
+    if cli.oldest || cli.newest {
+        cli.sort = Some(SortField::Date);
+        cli.reverse = cli.oldest;
+    }
+    // The TUI restores its last sort unless one was asked for explicitly
+    let sort_on_cli = cli.sort.is_some() || cli.reverse;
+
+    let config = config::load_config().unwrap_or_else(|e| {
+        eprintln!("Warning: {:#}; using default settings", e);
+        Config::default()
+    });
+    cli.apply_config(&config);
+    if let Some(ref template) = cli.export_name_template {
+        validate_name_template(template)?;
+    }
+    if let (Some(min), Some(max)) = (cli.min_size, cli.max_size) {
+        if min > max {
+            anyhow::bail!(
+                "--min-size ({}) is larger than --max-size ({})",
+                humansize::format_size(min, humansize::BINARY),
+                humansize::format_size(max, humansize::BINARY)
+            );
+        }
+    }
+
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let colors = Colors::new(cli.color.enabled(io::stdout().is_terminal(), no_color_env));
+    let chars_per_token = match cli.chars_per_token {
+        Some(n) if n.is_finite() && n > 0.0 => n,
+        Some(n) => {
+            eprintln!("Warning: chars_per_token = {} is not positive; using the default", n);
+            DEFAULT_CHARS_PER_TOKEN
+        }
+        None => DEFAULT_CHARS_PER_TOKEN,
+    };
+    // Deletes skip sessions Claude may still be writing unless forced
+    let protect_minutes = if cli.force { 0 } else { config.protect_recent_minutes };
 
     // Scan sessions
-    let mut sessions = scan_sessions()?;
+    let projects_dir = config.projects_dir()?;
+    if cli.doctor {
+        print_doctor(&config, &projects_dir, !cli.no_follow_symlinks);
+        return Ok(());
+    }
+    let scan = match scan_sessions(&projects_dir, !cli.no_follow_symlinks) {
+        Ok(scan) => scan,
+        Err(e) => {
+            eprintln!("Error: {:#}", e);
+            eprintln!("Check the path and its permissions, or set projects_dir in the config.");
+            std::process::exit(1);
+        }
+    };
+    for skipped in &scan.skipped {
+        eprintln!("Warning: skipping unreadable project directory {}", skipped);
+    }
+    let mut sessions = scan.sessions;
+
+    // Before any filters, so the rebuilt index covers every session
+    if cli.rebuild_cache {
+        SearchIndex::remove_file()?;
+        let mut index = SearchIndex::load();
+        let total = sessions.len();
+        for (i, session) in sessions.iter_mut().enumerate() {
+            let _ = load_session_metadata(session, chars_per_token);
+            index.update(session);
+            eprint!("\rReparsing sessions: {}/{}", i + 1, total);
+        }
+        if total > 0 {
+            eprintln!();
+        }
+        index.save()?;
+        println!("Rebuilt search index from {} session(s)", total);
+        return Ok(());
+    }
 
     // Filter by project if specified
     if let Some(ref proj_filter) = cli.project {
@@ -105,85 +571,266 @@ fn main() -> Result<()> {
         sessions.retain(|s| s.project.to_lowercase().contains(&filter_lower));
     }
 
-    // Sort sessions
-    match cli.sort {
-        SortField::Date => sessions.sort_by(|a, b| b.modified.cmp(&a.modified)),
-        SortField::Size => sessions.sort_by(|a, b| b.size_bytes.cmp(&a.size_bytes)),
-        SortField::Project => sessions.sort_by(|a, b| a.project.cmp(&b.project)),
-        SortField::Name => {
-            // Need to load metadata for name sorting
-            for session in &mut sessions {
-                let _ = load_session_metadata(session);
-            }
-            sessions.sort_by(|a, b| {
-                let name_a = a.summary.as_deref().or(a.first_message.as_deref()).unwrap_or("");
-                let name_b = b.summary.as_deref().or(b.first_message.as_deref()).unwrap_or("");
-                name_a.cmp(name_b)
-            });
+    // Filter by date range if specified; the TUI gets the rest back and filters them itself
+    let mut out_of_range = Vec::new();
+    if cli.since.is_some() || cli.until.is_some() {
+        let range = ui::Filter {
+            since: cli.since,
+            until: cli.until,
+            ..Default::default()
+        };
+        let (kept, dropped): (Vec<_>, Vec<_>) = sessions
+            .into_iter()
+            .partition(|s| range.in_date_range(s.modified));
+        sessions = kept;
+        out_of_range.extend(dropped);
+    }
+
+    // Filter by file size if specified (also left to the TUI, like the date range)
+    if cli.min_size.is_some() || cli.max_size.is_some() {
+        let range = ui::Filter {
+            min_size: cli.min_size,
+            max_size: cli.max_size,
+            ..Default::default()
+        };
+        let (kept, dropped): (Vec<_>, Vec<_>) = sessions
+            .into_iter()
+            .partition(|s| range.in_size_range(s.size_bytes));
+        sessions = kept;
+        out_of_range.extend(dropped);
+    }
+
+    if cli.since_last_run {
+        match config::load_last_run()? {
+            Some(last_run) => sessions.retain(|s| s.modified > last_run),
+            None => eprintln!("No previous run recorded; including all sessions"),
+        }
+        if cli.mark_run {
+            config::save_last_run(started)?;
         }
     }
 
-    // Reverse if requested
-    if cli.reverse {
-        sessions.reverse();
+    // Agent sub-sessions are hidden unless requested; the TUI keeps them so they can be toggled
+    let agent_sessions: Vec<session::Session> = if cli.include_agents {
+        Vec::new()
+    } else {
+        let (agents, rest) = sessions.into_iter().partition(|s| s.is_agent);
+        sessions = rest;
+        agents
+    };
+
+    if cli.projects {
+        // The config always supplies a sort, so only an explicit one replaces the count order
+        print_projects(&sessions, cli.sort.filter(|_| sort_on_cli), cli.reverse);
+        return Ok(());
     }
 
+    // Sort sessions (--reverse flips the order but keeps ties newest first)
+    let sort = cli.sort.unwrap_or_default();
+    if matches!(sort, SortField::Name | SortField::Created) {
+        // Need to load metadata for name and creation date sorting
+        for session in &mut sessions {
+            let _ = load_session_metadata(session, chars_per_token);
+        }
+    }
+    sessions.sort_by(|a, b| sort.compare(a, b, cli.reverse));
+
     if cli.count {
         println!("{}", sessions.len());
         return Ok(());
     }
 
-    if cli.stats {
+    if let Some(CountGroup::Project) = cli.count_by {
+        use std::collections::HashMap;
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for session in &sessions {
+            *counts.entry(session.project.as_str()).or_insert(0) += 1;
+        }
+
+        // Sort by count descending, then name for stable output
+        let mut counts: Vec<_> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        for (project, count) in counts {
+            if !writeln_safe(&format!("{}\t{}", project, count)) {
+                break; // Stop on broken pipe
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(ref path) = cli.export_csv {
+        for session in &mut sessions {
+            let _ = load_session_metadata(session, chars_per_token);
+        }
+        if path.as_os_str() == "-" {
+            actions::write_sessions_csv(&sessions, io::stdout().lock())?;
+        } else {
+            let file = std::fs::File::create(path)
+                .with_context(|| format!("Failed to create {:?}", path))?;
+            actions::write_sessions_csv(&sessions, file)?;
+            println!("Wrote {} session(s) to {}", sessions.len(), path.display());
+        }
+        return Ok(());
+    }
+
+    if cli.stats || cli.stats_by.is_some() {
         // Load metadata for all sessions to get token counts
         for session in &mut sessions {
-            let _ = load_session_metadata(session);
+            let _ = load_session_metadata(session, chars_per_token);
         }
 
-        // Aggregate by project
-        use std::collections::HashMap;
-        let mut project_stats: HashMap<String, (usize, u64, usize)> = HashMap::new(); // (count, size, tokens)
+        if let Some(StatsGroup::Model) = cli.stats_by {
+            if cli.json {
+                print_model_stats_json(&sessions)?;
+            } else {
+                print_model_stats(&sessions, colors);
+            }
+            return Ok(());
+        }
+
+        let group = cli.stats_by.unwrap_or_default();
+        let mut groups: std::collections::HashMap<String, GroupStats> =
+            std::collections::HashMap::new();
 
         for session in &sessions {
-            let entry = project_stats.entry(session.project.clone()).or_insert((0, 0, 0));
-            entry.0 += 1;
-            entry.1 += session.size_bytes;
-            entry.2 += session.token_count.unwrap_or(0);
+            let key = group
+                .period_key(session.modified)
+                .unwrap_or_else(|| session.project.clone());
+            let entry = groups.entry(key).or_default();
+            entry.count += 1;
+            entry.size += session.size_bytes;
+            entry.dir_size += session.dir_size_bytes;
+            entry.tokens += session.token_count.unwrap_or(0);
+            entry.attachments += session.attachment_count;
+            entry.add_date(session.modified);
         }
 
-        // Convert to vec and sort by size
-        let mut stats: Vec<_> = project_stats.into_iter().collect();
-        stats.sort_by(|a, b| b.1.1.cmp(&a.1.1)); // Sort by size descending
-
-        // Print header
-        println!("{:<20} {:>8} {:>12} {:>12}", "Project", "Sessions", "Size", "Tokens");
-        println!("{}", "-".repeat(56));
+        let mut stats: Vec<_> = groups.into_iter().collect();
+        // Time periods read best in order (their keys sort chronologically)
+        let by_project = matches!(group, StatsGroup::Project);
+        if by_project {
+            stats.sort_by_key(|(_, s)| std::cmp::Reverse(s.size + s.dir_size)); // Sort by size descending
+        } else {
+            stats.sort_by(|(a, _), (b, _)| a.cmp(b));
+        }
+        if cli.json {
+            print_stats_json(by_project, &stats)?;
+        } else {
+            print_stats_table(group.label(), &stats, colors);
+        }
 
-        let mut total_sessions = 0;
-        let mut total_size = 0u64;
-        let mut total_tokens = 0usize;
+        return Ok(());
+    }
 
-        for (project, (count, size, tokens)) in &stats {
-            println!(
-                "{:<20} {:>8} {:>12} {:>12}",
-                truncate_project(project, 20),
-                count,
-                humansize::format_size(*size, humansize::BINARY),
-                format_tokens(*tokens)
+    if let Some(ref id) = cli.delete {
+        // An explicit id can name an agent sub-session even when listings hide them
+        let session = resolve_session(sessions.iter().chain(&agent_sessions), id)?;
+        let dir_path = session.sidecar_dir();
+        println!(
+            "{} / {} ({})",
+            session.project,
+            session.id,
+            humansize::format_size(session.size_bytes, humansize::BINARY)
+        );
+        println!("  {}", session.path.display());
+        if dir_path.is_dir() {
+            println!("  {}/", dir_path.display());
+        }
+        if is_recently_active(session, protect_minutes) {
+            anyhow::bail!(
+                "{} was modified {} ago and may still be in use; pass --force to delete it",
+                session.id,
+                format_age(session.modified, chrono::Utc::now())
             );
-            total_sessions += count;
-            total_size += size;
-            total_tokens += tokens;
         }
 
-        println!("{}", "-".repeat(56));
+        if cli.dry_run {
+            return Ok(());
+        }
+        if !cli.yes && !confirm("Delete this session?")? {
+            println!("Aborted.");
+            return Ok(());
+        }
+
+        delete_session(session)?;
+        println!("Deleted {}", session.id);
+        return Ok(());
+    }
+
+    if cli.validate {
+        let mut with_issues = 0;
+        for session in &sessions {
+            let report = match validate_session_file(&session.path) {
+                Ok(report) => report,
+                Err(e) => {
+                    with_issues += 1;
+                    println!("{} / {}: {:#}", session.project, session.id, e);
+                    continue;
+                }
+            };
+            if report.is_clean() {
+                continue;
+            }
+
+            with_issues += 1;
+            println!("{} / {} ({} lines)", session.project, session.id, report.lines);
+            if !report.malformed.is_empty() {
+                println!(
+                    "  malformed JSON: {} line(s) ({})",
+                    report.malformed.len(),
+                    format_line_numbers(&report.malformed)
+                );
+            }
+            if !report.schema_errors.is_empty() {
+                let lines: Vec<usize> = report.schema_errors.iter().map(|(n, _)| *n).collect();
+                println!(
+                    "  unparseable records: {} line(s) ({}); first: {}",
+                    lines.len(),
+                    format_line_numbers(&lines),
+                    report.schema_errors[0].1
+                );
+            }
+            if !report.unknown_types.is_empty() {
+                let types: Vec<String> = report
+                    .unknown_types
+                    .iter()
+                    .map(|(name, count)| format!("{} x{}", name, count))
+                    .collect();
+                println!("  unknown record types: {}", types.join(", "));
+            }
+            if !report.missing_timestamps.is_empty() {
+                println!(
+                    "  missing timestamp: {} line(s) ({})",
+                    report.missing_timestamps.len(),
+                    format_line_numbers(&report.missing_timestamps)
+                );
+            }
+            if report.session_ids.len() > 1 {
+                let ids: Vec<&str> = report.session_ids.iter().map(String::as_str).collect();
+                println!("  inconsistent sessionId: {}", ids.join(", "));
+            }
+        }
+
         println!(
-            "{:<20} {:>8} {:>12} {:>12}",
-            "TOTAL",
-            total_sessions,
-            humansize::format_size(total_size, humansize::BINARY),
-            format_tokens(total_tokens)
+            "Checked {} session file(s): {} with issues",
+            sessions.len(),
+            with_issues
         );
+        if with_issues > 0 {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
 
+    if let Some(ref id) = cli.cat {
+        // Metadata supplies the front matter title and date
+        let mut session = resolve_session(sessions.iter().chain(&agent_sessions), id)?.clone();
+        let _ = load_session_metadata(&mut session, chars_per_token);
+        let options = cli.render_options();
+        let content = render_session(&session, cli.export_format.unwrap_or_default(), &options)?;
+        // Ignore write errors so `--cat | head` exits quietly on a closed pipe
+        let _ = io::stdout().write_all(content.as_bytes());
         return Ok(());
     }
 
@@ -192,15 +839,12 @@ fn main() -> Result<()> {
 
         // Find all empty sessions (sessions with 0 messages)
         for session in &mut sessions {
-            let _ = load_session_metadata(session);
-            let is_empty = session.message_count.map(|c| c == 0).unwrap_or(false)
-                && session.summary.is_none()
-                && session.first_message.is_none()
-                && session.custom_title.is_none();
-            if is_empty {
+            // Sessions we can't read aren't known to be empty
+            if load_session_metadata(session, chars_per_token).is_ok() && session.is_empty() {
                 empty_sessions.push(session.clone());
             }
         }
+        empty_sessions.retain(|s| !skip_recent(s, protect_minutes));
 
         if empty_sessions.is_empty() {
             println!("No empty sessions found.");
@@ -238,31 +882,797 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    if cli.list {
+    if cli.auto_prune {
+        let (empty_days, archive_days) = (config.prune_empty_after_days, config.archive_after_days);
+        if empty_days.is_none() && archive_days.is_none() {
+            println!(
+                "No auto-prune rules set; add prune_empty_after_days and/or archive_after_days to {}",
+                config::get_config_path()?.display()
+            );
+            return Ok(());
+        }
+
+        let now = chrono::Utc::now();
+        let older_than = |s: &Session, days: Option<u32>| {
+            days.is_some_and(|d| now.signed_duration_since(s.modified).num_days() >= d as i64)
+        };
+        let mut to_delete = Vec::new();
+        let mut to_archive = Vec::new();
         for session in &mut sessions {
-            // Load metadata to get summary/first message
-            let _ = load_session_metadata(session);
-            let preview = get_session_preview(session);
-            let line = format!(
-                "{}\t{}\t{}\t{}\t{}",
+            // Empty sessions are deleted rather than archived; unreadable ones are left alone
+            if older_than(session, empty_days)
+                && load_session_metadata(session, chars_per_token).is_ok()
+                && session.is_empty()
+            {
+                to_delete.push(session.clone());
+            } else if older_than(session, archive_days) {
+                to_archive.push(session.clone());
+            }
+        }
+        to_delete.retain(|s| !skip_recent(s, protect_minutes));
+        to_archive.retain(|s| !skip_recent(s, protect_minutes));
+
+        let archive_dir = actions::get_default_archive_dir()?;
+        let print_targets = |heading: String, targets: &[Session]| {
+            if targets.is_empty() {
+                return;
+            }
+            println!("{}", heading);
+            for session in targets {
+                println!(
+                    "  {} / {} ({})",
+                    session.project,
+                    session.id,
+                    humansize::format_size(session.size_bytes, humansize::BINARY)
+                );
+            }
+        };
+        let verb = |doing: &'static str, would: &'static str| if cli.dry_run { would } else { doing };
+        if let Some(days) = empty_days {
+            print_targets(
+                format!(
+                    "{} {} empty session(s) older than {} days:",
+                    verb("Deleting", "Would delete"),
+                    to_delete.len(),
+                    days
+                ),
+                &to_delete,
+            );
+        }
+        if let Some(days) = archive_days {
+            print_targets(
+                format!(
+                    "{} {} session(s) older than {} days to {}:",
+                    verb("Archiving and removing", "Would archive and remove"),
+                    to_archive.len(),
+                    days,
+                    archive_dir.display()
+                ),
+                &to_archive,
+            );
+        }
+        if to_delete.is_empty() && to_archive.is_empty() {
+            println!("Nothing to prune.");
+            return Ok(());
+        }
+        if cli.dry_run {
+            return Ok(());
+        }
+
+        let deleted = to_delete.iter().filter(|s| delete_session(s).is_ok()).count();
+        // Originals are only removed once their archive was written
+        let archived = to_archive
+            .iter()
+            .filter(|s| {
+                actions::archive_session(s, &archive_dir).is_ok() && delete_session(s).is_ok()
+            })
+            .count();
+        println!(
+            "Deleted {} empty session(s), archived and removed {} session(s)",
+            deleted, archived
+        );
+        return Ok(());
+    }
+
+    if cli.prune_duplicates {
+        // Sessions that fail to parse are left out rather than guessed at
+        let options = cli.render_options();
+        let parsed: Vec<(&Session, Vec<String>)> = sessions
+            .iter()
+            .filter_map(|s| {
+                let messages = load_session_messages(&s.path, &options).ok()?;
+                Some((s, user_message_lines(&messages)))
+            })
+            .collect();
+        let mut duplicates = find_duplicate_sessions(&parsed);
+        duplicates.retain(|&(dup, _)| !skip_recent(parsed[dup].0, protect_minutes));
+
+        if duplicates.is_empty() {
+            println!("No duplicate sessions found.");
+            return Ok(());
+        }
+
+        println!(
+            "{} {} duplicate session(s):",
+            if cli.dry_run { "Would delete" } else { "Found" },
+            duplicates.len()
+        );
+        for &(dup, kept) in &duplicates {
+            let (session, lines) = &parsed[dup];
+            println!(
+                "  {} / {} ({}, {} user messages) -> kept in {}",
                 session.project,
                 session.id,
-                session.modified.format("%Y-%m-%d %H:%M"),
                 humansize::format_size(session.size_bytes, humansize::BINARY),
-                preview
+                lines.len(),
+                parsed[kept].0.id
             );
+        }
+
+        if cli.dry_run {
+            return Ok(());
+        }
+        if !cli.yes && !confirm("Delete these sessions?")? {
+            println!("Aborted.");
+            return Ok(());
+        }
+
+        let mut deleted = 0;
+        let mut total_size = 0u64;
+        for &(dup, _) in &duplicates {
+            let session = parsed[dup].0;
+            if delete_session(session).is_ok() {
+                deleted += 1;
+                total_size += session.size_bytes;
+            }
+        }
+        println!(
+            "Deleted {} session(s), freed {}",
+            deleted,
+            humansize::format_size(total_size, humansize::BINARY)
+        );
+        return Ok(());
+    }
+
+    if let Some(ref query) = cli.select_matching {
+        for session in &mut sessions {
+            let _ = load_session_metadata(session, chars_per_token);
+        }
+        let query_lower = query.to_lowercase();
+        let mut state = UiState::new(sessions);
+        state.filter.include_agents = true; // already excluded above unless --include-agents
+        state.select_matching(|s| {
+            s.search_content
+                .as_deref()
+                .is_some_and(|c| c.contains(&query_lower))
+        });
+
+        let mut indices: Vec<usize> = state.selected.iter().copied().collect();
+        indices.sort_unstable();
+        let targets: Vec<&Session> = indices.iter().map(|&idx| &state.sessions[idx]).collect();
+
+        if targets.is_empty() {
+            println!("No sessions match '{}'.", query);
+            return Ok(());
+        }
+
+        let action = cli.bulk.unwrap_or_default();
+        if cli.archive_name.is_some() && !matches!(action, BulkAction::Archive) {
+            anyhow::bail!("--archive-name needs --bulk archive");
+        }
+        for session in &targets {
+            println!(
+                "  {} / {} ({})",
+                session.project,
+                session.id,
+                humansize::format_size(session.size_bytes, humansize::BINARY)
+            );
+        }
+        println!("{} session(s) match '{}'", targets.len(), query);
+
+        match action {
+            BulkAction::List => {}
+            _ if cli.dry_run => {}
+            BulkAction::Delete => {
+                let targets: Vec<&Session> = targets
+                    .into_iter()
+                    .filter(|s| !skip_recent(s, protect_minutes))
+                    .collect();
+                if targets.is_empty() {
+                    return Ok(());
+                }
+                if !cli.yes && !confirm("Delete these sessions?")? {
+                    println!("Aborted.");
+                    return Ok(());
+                }
+                let deleted = delete_sessions(&targets)?;
+                println!("Deleted {} session(s)", deleted);
+            }
+            BulkAction::Archive if cli.archive_name.is_some() => {
+                let dir = actions::get_default_archive_dir()?;
+                let name = cli.archive_name.as_deref().unwrap_or_default();
+                let path = actions::archive_batch(&targets, &dir, name)?;
+                println!("Archived {} session(s) to {}", targets.len(), path.display());
+            }
+            BulkAction::Archive => {
+                let dir = actions::get_default_archive_dir()?;
+                let archived = targets
+                    .iter()
+                    .filter(|s| actions::archive_session(s, &dir).is_ok())
+                    .count();
+                println!("Archived {} session(s) to {}", archived, dir.display());
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(ref query) = cli.search {
+        let now = chrono::Utc::now();
+        let query_lower = query.to_lowercase();
+        let mut index = SearchIndex::load();
+        let candidates = index.candidates(&query_lower);
+        for session in &mut sessions {
+            // Unchanged sessions the index rules out don't need parsing
+            if candidates.as_ref().is_some_and(|c| !index.may_match(session, c)) {
+                continue;
+            }
+            let _ = load_session_metadata(session, chars_per_token);
+            index.update(session);
+            let snippet = match session
+                .search_content
+                .as_deref()
+                .and_then(|content| find_snippet(content, &query_lower, 30))
+            {
+                Some(snippet) => snippet,
+                None => continue,
+            };
+            let line = format!("{}\t{}", format_list_line(session, &cli, now, colors), snippet);
             if !writeln_safe(&line) {
                 break; // Stop on broken pipe
             }
         }
+        save_search_index(&mut index);
         return Ok(());
     }
 
+    if let Some(ref term) = cli.grep {
+        let now = chrono::Utc::now();
+        let term_lower = term.to_lowercase();
+        let mut index = SearchIndex::load();
+        let candidates = index.candidates(&term_lower);
+        for session in &mut sessions {
+            if candidates.as_ref().is_some_and(|c| !index.may_match(session, c)) {
+                continue;
+            }
+            let _ = load_session_metadata(session, chars_per_token);
+            index.update(session);
+            // Cheap pre-check against the indexed content before reading messages
+            let indexed = session
+                .search_content
+                .as_deref()
+                .is_some_and(|c| c.contains(&term_lower));
+            if !indexed {
+                continue;
+            }
+
+            let messages = match load_session_messages(&session.path, &cli.render_options()) {
+                Ok(m) => m,
+                Err(_) => continue,
+            };
+            let matched = messages.iter().find_map(|msg| {
+                msg.content
+                    .lines()
+                    .find(|line| line.to_lowercase().contains(&term_lower))
+                    .map(|line| (msg.role, line.trim()))
+            });
+            if let Some((role, line)) = matched {
+                let role = match role {
+                    MessageRole::User => "User",
+                    MessageRole::Assistant => "Assistant",
+                    MessageRole::System => "System",
+                };
+                let line: String = if line.chars().count() > 120 {
+                    format!("{}...", line.chars().take(117).collect::<String>())
+                } else {
+                    line.to_string()
+                };
+                let output = format!(
+                    "{}\t{}: {}",
+                    format_list_line(session, &cli, now, colors),
+                    role,
+                    line
+                );
+                if !writeln_safe(&output) {
+                    break; // Stop on broken pipe
+                }
+            }
+        }
+        save_search_index(&mut index);
+        return Ok(());
+    }
+
+    // Pagination applies to the listing modes only; --count reports the full total
+    let limit = cli.limit.unwrap_or(usize::MAX);
+
+    if cli.ids || cli.print0 {
+        let separator = if cli.print0 { "\0" } else { "\n" };
+        let mut stdout = io::stdout().lock();
+        for session in sessions.iter().skip(cli.offset).take(limit) {
+            if write!(stdout, "{}{}", session.id, separator).is_err() {
+                break; // Stop on broken pipe
+            }
+        }
+        return Ok(());
+    }
+
+    if cli.list {
+        let now = chrono::Utc::now();
+        for session in sessions.iter_mut().skip(cli.offset).take(limit) {
+            // Load metadata to get summary/first message
+            let _ = load_session_metadata(session, chars_per_token);
+            if !writeln_safe(&format_list_line(session, &cli, now, colors)) {
+                break; // Stop on broken pipe
+            }
+        }
+        return Ok(());
+    }
+
+    // Validate the theme before entering the TUI so the warning is visible
+    let highlighter = match cli.theme {
+        Some(ref name) => {
+            let highlighter = Highlighter::with_theme(name);
+            if highlighter.theme_name() != name {
+                let mut themes = highlighter.available_themes();
+                themes.sort();
+                eprintln!(
+                    "Warning: unknown theme '{}', using '{}'. Available: {}",
+                    name,
+                    highlighter.theme_name(),
+                    themes.join(", ")
+                );
+            }
+            highlighter
+        }
+        None => Highlighter::new(),
+    };
+
     // Run TUI
-    run_tui(sessions)
+    sessions.extend(agent_sessions);
+    sessions.extend(out_of_range);
+    if let Some(ref id) = cli.view {
+        sessions = vec![resolve_session(&sessions, id)?.clone()];
+    }
+    let mut state = UiState::new(sessions);
+    state.preview_only = cli.view.is_some();
+    state.filter.include_agents = cli.include_agents;
+    state.filter.since = cli.since;
+    state.filter.until = cli.until;
+    state.filter.min_size = cli.min_size;
+    state.filter.max_size = cli.max_size;
+    state.relative_time = cli.relative_time;
+    state.export_format = cli.export_format.unwrap_or_default();
+    state.export_dir = cli.export_dir.clone();
+    if let Some(ref template) = cli.export_name_template {
+        state.export_name_template = template.clone();
+    }
+    state.sort_field = cli.sort.unwrap_or_default();
+    state.sort_reversed = cli.reverse;
+    if let Some(ref columns) = cli.columns {
+        state.columns = columns.clone();
+    }
+    state.older_than_days = config.older_than_days;
+    state.active_days = config.active_days;
+    state.protect_recent_minutes = config.protect_recent_minutes;
+    state.preview_wrap = config.preview_wrap;
+    state.preview_markdown = config.preview_markdown;
+    state.float_active = config.float_active_session;
+    state.color_theme = config.color_theme;
+    state.price_per_million_tokens = config.price_per_million_tokens;
+    state.projects_dir = projects_dir;
+    state.follow_symlinks = !cli.no_follow_symlinks;
+    state.search_index = SearchIndex::load();
+    state.watch = cli.watch;
+    state.chars_per_token = chars_per_token;
+    state.terminal_command = config.terminal_command();
+    state.render_options = cli.render_options();
+    match session::load_bookmarks() {
+        Ok(pinned) => state.pinned = pinned,
+        Err(e) => state.set_status(format!("Failed to load pins: {}", e)),
+    }
+    match config::load_view_state() {
+        Ok(Some(view)) => {
+            if !sort_on_cli {
+                state.sort_field = view.sort;
+                state.sort_reversed = view.reverse;
+            }
+            if let (None, Some(project), false) = (&cli.project, view.project, state.preview_only) {
+                state.set_project_filter(&project);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => state.set_status(format!("Failed to restore view: {:#}", e)),
+    }
+    if state.preview_only {
+        state.filter.include_agents = true; // --view may name an agent session
+    }
+    state.apply_filters();
+
+    run_tui(state, highlighter)
+}
+
+/// Persist search index updates; a stale index only costs speed, so failures just warn
+fn save_search_index(index: &mut SearchIndex) {
+    index.prune_missing();
+    if let Err(e) = index.save() {
+        eprintln!("Warning: {:#}", e);
+    }
+}
+
+/// Ask a yes/no question on stdin (defaults to no)
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{} [y/N] ", prompt);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+/// Format a session as a tab-separated `--list` line
+fn format_list_line(
+    session: &session::Session,
+    cli: &Cli,
+    now: chrono::DateTime<chrono::Utc>,
+    colors: Colors,
+) -> String {
+    let date = cli
+        .relative_time
+        .then(|| format_relative_time(session.modified, now))
+        .flatten()
+        .unwrap_or_else(|| format_time(session.modified, "%Y-%m-%d %H:%M", cli.local_time));
+    let size = humansize::format_size(session.size_bytes, humansize::BINARY);
+    let size = if session.size_bytes >= LARGE_SESSION_BYTES {
+        colors.yellow(&size)
+    } else {
+        size
+    };
+    let messages = session
+        .message_count
+        .map(|n| n.to_string())
+        .unwrap_or_else(|| "-".to_string());
+    format!(
+        "{}\t{}\t{}\t{}\t{}\t{}",
+        session.project,
+        session.id,
+        colors.dim(&date),
+        size,
+        messages,
+        get_session_preview(session)
+    )
+}
+
+/// Print `--doctor` environment checks, one `label: value` line each
+fn print_doctor(config: &Config, projects_dir: &std::path::Path, follow_symlinks: bool) {
+    let line = |label: &str, value: String| println!("{:<14} {}", format!("{}:", label), value);
+    let size = |bytes: u64| humansize::format_size(bytes, humansize::BINARY);
+
+    line(
+        "Version",
+        format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION")),
+    );
+    line(
+        "Config",
+        match config::get_config_path() {
+            Ok(path) if path.exists() => path.display().to_string(),
+            Ok(path) => format!("{} (not found, using defaults)", path.display()),
+            Err(e) => format!("unavailable ({:#})", e),
+        },
+    );
+
+    let scan = scan_sessions(projects_dir, follow_symlinks);
+    line(
+        "Projects dir",
+        match &scan {
+            Err(e) => format!("{:#}", e),
+            Ok(_) if !projects_dir.exists() => format!("{} (missing)", projects_dir.display()),
+            Ok(_) => format!("{} (readable)", projects_dir.display()),
+        },
+    );
+
+    let sessions = match scan {
+        Ok(scan) => {
+            for skipped in &scan.skipped {
+                line("Skipped", skipped.clone());
+            }
+            scan.sessions
+        }
+        Err(_) => Vec::new(),
+    };
+    let projects: std::collections::HashSet<&str> =
+        sessions.iter().map(|s| s.project_raw.as_str()).collect();
+    let agents = sessions.iter().filter(|s| s.is_agent).count();
+    line(
+        "Sessions",
+        format!(
+            "{} in {} project(s) ({} agent sub-sessions)",
+            sessions.len(),
+            projects.len(),
+            agents
+        ),
+    );
+    let files: u64 = sessions.iter().map(|s| s.size_bytes).sum();
+    let dirs: u64 = sessions.iter().map(|s| s.dir_size_bytes).sum();
+    line(
+        "Disk usage",
+        format!("{} ({} sessions, {} sidecar dirs)", size(files + dirs), size(files), size(dirs)),
+    );
+
+    line(
+        "Search index",
+        match SearchIndex::path() {
+            Ok(path) if path.exists() => {
+                let index = SearchIndex::load();
+                let fresh = sessions.iter().filter(|s| index.is_fresh(s)).count();
+                format!(
+                    "{} ({}, {} indexed, {} of {} up to date)",
+                    path.display(),
+                    size(std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0)),
+                    index.indexed_count(),
+                    fresh,
+                    sessions.len()
+                )
+            }
+            Ok(path) => format!("{} (not built yet)", path.display()),
+            Err(e) => format!("unavailable ({:#})", e),
+        },
+    );
+
+    let (clipboard, tried) = ui::app::detect_clipboard_tool();
+    line(
+        "Clipboard",
+        match clipboard {
+            Some(tool) => tool.to_string(),
+            None => format!("none found (tried: {})", tried.join(", ")),
+        },
+    );
+    let (dir_command, in_terminal) = ui::app::open_dir_command();
+    line(
+        "Open dir (O)",
+        format!(
+            "{}{}",
+            dir_command.join(" "),
+            if in_terminal { "" } else { " ($VISUAL/$EDITOR not set)" }
+        ),
+    );
+    line("Viewer (X)", ui::app::open_file_command().join(" "));
+    line(
+        "Resume (R)",
+        config
+            .terminal_command()
+            .unwrap_or_else(|| "in this terminal (no terminal configured)".to_string()),
+    );
 }
 
-fn run_tui(sessions: Vec<session::Session>) -> Result<()> {
+/// Print grouped stats as an aligned table with a TOTAL row
+fn print_stats_table(label: &str, stats: &[(String, GroupStats)], colors: Colors) {
+    println!(
+        "{}",
+        colors.bold(&format!(
+            "{:<20} {:>8} {:>12} {:>12} {:>12} {:>8} {:>10} {:>10}",
+            label, "Sessions", "Size", "Dir Size", "Tokens", "Attach", "First", "Last"
+        ))
+    );
+    println!("{}", colors.dim(&"-".repeat(100)));
+
+    let mut total = GroupStats::default();
+    let date = |d: Option<DateTime<Utc>>| {
+        d.map_or_else(|| "-".to_string(), |d| d.format("%Y-%m-%d").to_string())
+    };
+
+    for (name, s) in stats {
+        println!(
+            "{:<20} {:>8} {:>12} {:>12} {:>12} {:>8} {:>10} {:>10}",
+            truncate_project(name, 20),
+            s.count,
+            humansize::format_size(s.size, humansize::BINARY),
+            humansize::format_size(s.dir_size, humansize::BINARY),
+            format_tokens(s.tokens),
+            s.attachments,
+            date(s.first),
+            date(s.last)
+        );
+        total.count += s.count;
+        total.size += s.size;
+        total.dir_size += s.dir_size;
+        total.tokens += s.tokens;
+        total.attachments += s.attachments;
+        s.first.into_iter().chain(s.last).for_each(|d| total.add_date(d));
+    }
+
+    println!("{}", colors.dim(&"-".repeat(100)));
+    println!(
+        "{}",
+        colors.bold(&format!(
+            "{:<20} {:>8} {:>12} {:>12} {:>12} {:>8} {:>10} {:>10}",
+            "TOTAL",
+            total.count,
+            humansize::format_size(total.size, humansize::BINARY),
+            humansize::format_size(total.dir_size, humansize::BINARY),
+            format_tokens(total.tokens),
+            total.attachments,
+            date(total.first),
+            date(total.last)
+        ))
+    );
+}
+
+/// Print grouped stats as pretty JSON rows plus a totals object
+fn print_stats_json(by_project: bool, stats: &[(String, GroupStats)]) -> Result<()> {
+    let mut totals = StatsRow {
+        project: None,
+        period: None,
+        sessions: 0,
+        size_bytes: 0,
+        dir_size_bytes: 0,
+        tokens: 0,
+        attachments: 0,
+        first: None,
+        last: None,
+    };
+    let rows = stats
+        .iter()
+        .map(|(name, s)| {
+            totals.sessions += s.count;
+            totals.size_bytes += s.size;
+            totals.dir_size_bytes += s.dir_size;
+            totals.tokens += s.tokens;
+            totals.attachments += s.attachments;
+            totals.first = totals.first.into_iter().chain(s.first).min();
+            totals.last = totals.last.into_iter().chain(s.last).max();
+            StatsRow {
+                project: by_project.then_some(name.as_str()),
+                period: (!by_project).then_some(name.as_str()),
+                sessions: s.count,
+                size_bytes: s.size,
+                dir_size_bytes: s.dir_size,
+                tokens: s.tokens,
+                attachments: s.attachments,
+                first: s.first,
+                last: s.last,
+            }
+        })
+        .collect();
+    writeln_safe(&serde_json::to_string_pretty(&StatsReport { rows, totals })?);
+    Ok(())
+}
+
+/// Sessions and tokens per assistant model, most tokens first
+fn model_stats(sessions: &[session::Session]) -> Vec<(&str, (usize, usize))> {
+    use std::collections::HashMap;
+    let mut model_stats: HashMap<&str, (usize, usize)> = HashMap::new(); // (count, tokens)
+
+    for session in sessions {
+        for (model, tokens) in &session.model_tokens {
+            let entry = model_stats.entry(model.as_str()).or_insert((0, 0));
+            entry.0 += 1;
+            entry.1 += tokens;
+        }
+    }
+
+    let mut stats: Vec<_> = model_stats.into_iter().collect();
+    stats.sort_by_key(|(_, (_, tokens))| std::cmp::Reverse(*tokens)); // Sort by tokens descending
+    stats
+}
+
+/// Print model stats as pretty JSON rows plus a totals object
+fn print_model_stats_json(sessions: &[session::Session]) -> Result<()> {
+    let rows: Vec<ModelStatsRow> = model_stats(sessions)
+        .into_iter()
+        .map(|(model, (count, tokens))| ModelStatsRow {
+            model: Some(model),
+            sessions: count,
+            tokens,
+        })
+        .collect();
+    let totals = ModelStatsRow {
+        model: None,
+        sessions: sessions.iter().filter(|s| !s.model_tokens.is_empty()).count(),
+        tokens: rows.iter().map(|r| r.tokens).sum(),
+    };
+    writeln_safe(&serde_json::to_string_pretty(&StatsReport { rows, totals })?);
+    Ok(())
+}
+
+/// Print each project's decoded path with its session count
+///
+/// Ordered by session count unless a sort is given: project/name sort by path, size by
+/// total size and date by the most recent session.
+fn print_projects(sessions: &[session::Session], sort: Option<SortField>, reverse: bool) {
+    struct ProjectRow<'a> {
+        path: &'a str,
+        count: usize,
+        size: u64,
+        latest: DateTime<Utc>,
+    }
+
+    let mut rows: Vec<ProjectRow> = session::get_project_names(sessions)
+        .iter()
+        .filter_map(|name| {
+            let mut matching = sessions.iter().filter(|s| &s.project == name);
+            let first = matching.next()?;
+            let mut row = ProjectRow {
+                path: &first.project_path,
+                count: 1,
+                size: first.size_bytes,
+                latest: first.modified,
+            };
+            for s in matching {
+                row.count += 1;
+                row.size += s.size_bytes;
+                row.latest = row.latest.max(s.modified);
+            }
+            Some(row)
+        })
+        .collect();
+
+    rows.sort_by(|a, b| {
+        let primary = match sort {
+            None => b.count.cmp(&a.count),
+            Some(SortField::Project | SortField::Name) => std::cmp::Ordering::Equal,
+            Some(SortField::Size) => b.size.cmp(&a.size),
+            // Creation dates need metadata, so both date sorts use the newest session
+            Some(SortField::Date | SortField::Created) => b.latest.cmp(&a.latest),
+        };
+        let ordering = primary.then_with(|| a.path.cmp(b.path));
+        if reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+
+    for row in rows {
+        if !writeln_safe(&format!("{}\t{}", row.path, row.count)) {
+            break; // Stop on broken pipe
+        }
+    }
+}
+
+/// Print token usage aggregated by assistant model
+fn print_model_stats(sessions: &[session::Session], colors: Colors) {
+    let stats = model_stats(sessions);
+
+    // Print header
+    println!(
+        "{}",
+        colors.bold(&format!("{:<32} {:>8} {:>12}", StatsGroup::Model.label(), "Sessions", "Tokens"))
+    );
+    println!("{}", colors.dim(&"-".repeat(54)));
+
+    let mut total_tokens = 0usize;
+
+    for (model, (count, tokens)) in &stats {
+        println!(
+            "{:<32} {:>8} {:>12}",
+            truncate_project(model, 32),
+            count,
+            format_tokens(*tokens)
+        );
+        total_tokens += tokens;
+    }
+
+    println!("{}", colors.dim(&"-".repeat(54)));
+    println!(
+        "{}",
+        colors.bold(&format!(
+            "{:<32} {:>8} {:>12}",
+            "TOTAL",
+            sessions.iter().filter(|s| !s.model_tokens.is_empty()).count(),
+            format_tokens(total_tokens)
+        ))
+    );
+}
+
+fn run_tui(state: UiState, highlighter: Highlighter) -> Result<()> {
     // Setup terminal
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -270,9 +1680,7 @@ fn run_tui(sessions: Vec<session::Session>) -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app state
-    let state = UiState::new(sessions);
-    let mut app = App::new(state);
+    let mut app = App::new(state, highlighter);
 
     // Run app
     let result = app.run(&mut terminal);
@@ -282,5 +1690,41 @@ fn run_tui(sessions: Vec<session::Session>) -> Result<()> {
     execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
     terminal.show_cursor()?;
 
-    result
+    // Remember sort and project filter for next time (--view never changes them)
+    if !app.state.preview_only {
+        let view = config::ViewState {
+            sort: app.state.sort_field,
+            reverse: app.state.sort_reversed,
+            project: app.state.filter.project.clone(),
+        };
+        if let Err(e) = config::save_view_state(&view) {
+            eprintln!("Warning: {:#}", e);
+        }
+    }
+
+    result?;
+    match app.resume_on_exit.take() {
+        Some((dir, id)) => resume_in_place(&dir, &id),
+        None => Ok(()),
+    }
+}
+
+/// Replace this process with `claude --resume <id>` in the session's directory
+fn resume_in_place(dir: &std::path::Path, id: &str) -> Result<()> {
+    let mut command = std::process::Command::new("claude");
+    command.arg("--resume").arg(id).current_dir(dir);
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        let err = command.exec(); // Only returns on failure
+        Err(anyhow::Error::new(err).context("Failed to run claude"))
+    }
+    #[cfg(not(unix))]
+    {
+        let status = command
+            .status()
+            .map_err(|e| anyhow::Error::new(e).context("Failed to run claude"))?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
 }
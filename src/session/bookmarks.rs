@@ -0,0 +1,39 @@
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+/// Get the bookmarks file path (~/.claude/.ccsessionctl-bookmarks.json)
+fn get_bookmarks_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    Ok(home.join(".claude").join(".ccsessionctl-bookmarks.json"))
+}
+
+/// Load pinned session ids (empty if the file doesn't exist yet)
+pub fn load_bookmarks() -> Result<HashSet<String>> {
+    let path = get_bookmarks_path()?;
+    if !path.exists() {
+        return Ok(HashSet::new());
+    }
+
+    let content =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {:?}", path))?;
+    let ids: Vec<String> =
+        serde_json::from_str(&content).with_context(|| format!("Failed to parse {:?}", path))?;
+    Ok(ids.into_iter().collect())
+}
+
+/// Save pinned session ids
+pub fn save_bookmarks(ids: &HashSet<String>) -> Result<()> {
+    let path = get_bookmarks_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    // Sorted for stable diffs
+    let mut sorted: Vec<&String> = ids.iter().collect();
+    sorted.sort();
+    let content = serde_json::to_string_pretty(&sorted)?;
+    fs::write(&path, content).with_context(|| format!("Failed to write {:?}", path))?;
+    Ok(())
+}
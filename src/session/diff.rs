@@ -0,0 +1,247 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use super::types::{DisplayMessage, MessageRole, Session};
+
+/// One line of a unified diff between two sessions' user messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffOp {
+    /// Present in both sessions
+    Same(String),
+    /// Only in the first (older) session
+    Left(String),
+    /// Only in the second (newer) session
+    Right(String),
+}
+
+/// Diff the user messages of two sessions (compared by their first line)
+///
+/// Uses a longest-common-subsequence alignment, so a resumed fork shows up as a shared
+/// run of `Same` followed by the messages each branch added.
+pub fn diff_user_messages(left: &[DisplayMessage], right: &[DisplayMessage]) -> Vec<DiffOp> {
+    let a = user_message_lines(left);
+    let b = user_message_lines(right);
+
+    // lcs[i][j] = length of the LCS of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < a.len() && j < b.len() {
+        if a[i] == b[j] {
+            ops.push(DiffOp::Same(a[i].clone()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            ops.push(DiffOp::Left(a[i].clone()));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Right(b[j].clone()));
+            j += 1;
+        }
+    }
+    ops.extend(a[i..].iter().cloned().map(DiffOp::Left));
+    ops.extend(b[j..].iter().cloned().map(DiffOp::Right));
+    ops
+}
+
+/// Number of user messages the two sessions share before they diverge
+pub fn shared_prefix_len(ops: &[DiffOp]) -> usize {
+    ops.iter()
+        .take_while(|op| matches!(op, DiffOp::Same(_)))
+        .count()
+}
+
+/// Find sessions that are forked/resumed duplicates of a longer session
+///
+/// A session is a duplicate when another session in the same project starts with the
+/// same user messages and ranks higher (more user messages, then larger, newer, id).
+/// Because the ranking is total, the longest variant of each group is never returned.
+/// Takes each session with its `user_message_lines` and returns `(duplicate, kept)` index pairs.
+pub fn find_duplicate_sessions(sessions: &[(&Session, Vec<String>)]) -> Vec<(usize, usize)> {
+    let rank = |i: usize| {
+        let (session, lines) = &sessions[i];
+        (lines.len(), session.size_bytes, session.modified, &session.id)
+    };
+
+    let mut duplicates = Vec::new();
+    for (i, (session, lines)) in sessions.iter().enumerate() {
+        if lines.is_empty() {
+            continue;
+        }
+        // Prefer the highest-ranked session that contains this one
+        let keeper = sessions
+            .iter()
+            .enumerate()
+            .filter(|(j, (other, other_lines))| {
+                *j != i
+                    && other.project == session.project
+                    && other_lines.starts_with(lines)
+                    && rank(*j).cmp(&rank(i)) == Ordering::Greater
+            })
+            .max_by(|(a, _), (b, _)| rank(*a).cmp(&rank(*b)))
+            .map(|(j, _)| j);
+
+        if let Some(j) = keeper {
+            duplicates.push((i, j));
+        }
+    }
+    duplicates
+}
+
+/// Merge the messages of a resume chain into one deduplicated conversation
+///
+/// A resumed session starts with a copy of the conversation it continues, so messages
+/// with the same role, timestamp and content are kept only once. The result is in
+/// timestamp order; messages with equal timestamps keep their chain order.
+pub fn merge_session_messages(chain: Vec<Vec<DisplayMessage>>) -> Vec<DisplayMessage> {
+    let mut seen = HashSet::new();
+    let mut merged: Vec<DisplayMessage> = chain
+        .into_iter()
+        .flatten()
+        .filter(|m| seen.insert((m.role, m.timestamp, m.content.clone())))
+        .collect();
+    merged.sort_by_key(|m| m.timestamp);
+    merged
+}
+
+/// First line of each user message, trimmed
+pub fn user_message_lines(messages: &[DisplayMessage]) -> Vec<String> {
+    messages
+        .iter()
+        .filter(|m| m.role == MessageRole::User)
+        .map(|m| m.content.lines().next().unwrap_or_default().trim().to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn user(text: &str) -> DisplayMessage {
+        DisplayMessage {
+            role: MessageRole::User,
+            timestamp: Utc::now(),
+            content: text.to_string(),
+        }
+    }
+
+    fn assistant(text: &str) -> DisplayMessage {
+        DisplayMessage {
+            role: MessageRole::Assistant,
+            timestamp: Utc::now(),
+            content: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diff_forked_sessions() {
+        let left = vec![user("fix the parser"), assistant("done"), user("add tests")];
+        let right = vec![user("fix the parser"), assistant("ok"), user("write docs")];
+
+        let ops = diff_user_messages(&left, &right);
+        assert_eq!(
+            ops,
+            vec![
+                DiffOp::Same("fix the parser".to_string()),
+                DiffOp::Left("add tests".to_string()),
+                DiffOp::Right("write docs".to_string()),
+            ]
+        );
+        assert_eq!(shared_prefix_len(&ops), 1);
+    }
+
+    #[test]
+    fn test_merge_session_messages_drops_resumed_copies() {
+        let at = |minute: u32, role: MessageRole, text: &str| DisplayMessage {
+            role,
+            timestamp: Utc.with_ymd_and_hms(2026, 1, 1, 10, minute, 0).unwrap(),
+            content: text.to_string(),
+        };
+        let first = vec![
+            at(0, MessageRole::User, "fix the parser"),
+            at(1, MessageRole::Assistant, "done"),
+        ];
+        let resumed = vec![
+            at(0, MessageRole::User, "fix the parser"),
+            at(1, MessageRole::Assistant, "done"),
+            at(5, MessageRole::User, "add tests"),
+            at(6, MessageRole::Assistant, "added"),
+        ];
+
+        // Chain order doesn't matter: the result follows the timestamps
+        let merged = merge_session_messages(vec![resumed, first]);
+        let contents: Vec<&str> = merged.iter().map(|m| m.content.as_str()).collect();
+        assert_eq!(contents, vec!["fix the parser", "done", "add tests", "added"]);
+    }
+
+    fn make_session(id: &str, project: &str, size: u64) -> Session {
+        Session {
+            project: project.to_string(),
+            size_bytes: size,
+            ..Session::test(id)
+        }
+    }
+
+    fn lines(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_find_duplicates_keeps_longest() {
+        let (short, long, other) = (
+            make_session("short", "proj", 10),
+            make_session("long", "proj", 20),
+            make_session("other", "elsewhere", 5),
+        );
+        let sessions = vec![
+            (&short, lines(&["a"])),
+            (&long, lines(&["a", "b"])),
+            (&other, lines(&["a"])),
+        ];
+        assert_eq!(find_duplicate_sessions(&sessions), vec![(0, 1)]);
+    }
+
+    #[test]
+    fn test_find_duplicates_identical_keeps_one() {
+        let (a, b) = (make_session("a", "proj", 10), make_session("b", "proj", 10));
+        let sessions = vec![(&a, lines(&["x", "y"])), (&b, lines(&["x", "y"]))];
+
+        let duplicates = find_duplicate_sessions(&sessions);
+        assert_eq!(duplicates.len(), 1);
+        assert_eq!(duplicates[0], (0, 1));
+    }
+
+    #[test]
+    fn test_find_duplicates_ignores_divergent_and_empty() {
+        let (a, b, c) = (
+            make_session("a", "proj", 10),
+            make_session("b", "proj", 20),
+            make_session("c", "proj", 0),
+        );
+        let sessions = vec![
+            (&a, lines(&["x", "y"])),
+            (&b, lines(&["x", "z", "w"])),
+            (&c, Vec::new()),
+        ];
+        assert!(find_duplicate_sessions(&sessions).is_empty());
+    }
+
+    #[test]
+    fn test_diff_identical_sessions() {
+        let messages = vec![user("a"), user("b\nmore detail")];
+        let ops = diff_user_messages(&messages, &messages);
+        assert!(ops.iter().all(|op| matches!(op, DiffOp::Same(_))));
+        assert_eq!(shared_prefix_len(&ops), 2);
+    }
+}
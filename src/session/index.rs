@@ -0,0 +1,308 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::PathBuf;
+
+use super::types::Session;
+
+/// Bump when the tokenizer or file layout changes; older indexes are rebuilt
+const INDEX_VERSION: u32 = 2;
+
+/// Terms shorter than this aren't indexed (and don't narrow a query)
+const MIN_TERM_LEN: usize = 2;
+
+/// Persistent inverted index (term -> sessions) over session search content
+///
+/// Entries are keyed by session id and stamped with the file's mtime and size, so only
+/// sessions that changed on disk need reparsing, and renaming a project directory (which
+/// moves files without changing them) doesn't orphan them. The index only narrows the
+/// candidates: callers still do exact matching on the sessions it returns.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SearchIndex {
+    version: u32,
+    next_doc: u32,
+    docs: BTreeMap<String, IndexedFile>,
+    terms: BTreeMap<String, Vec<u32>>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedFile {
+    doc: u32,
+    /// Where the session file was last seen, for pruning deleted sessions
+    path: PathBuf,
+    modified: DateTime<Utc>,
+    size: u64,
+}
+
+/// Get the index file path (~/.claude/.ccsessionctl-index)
+fn get_index_path() -> Result<PathBuf> {
+    let home = dirs::home_dir().context("Could not find home directory")?;
+    Ok(home.join(".claude").join(".ccsessionctl-index"))
+}
+
+impl SearchIndex {
+    /// Where the index file is stored
+    pub fn path() -> Result<PathBuf> {
+        get_index_path()
+    }
+
+    /// Number of sessions in the index
+    pub fn indexed_count(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Load the index, starting fresh if it is missing, unreadable or outdated
+    pub fn load() -> Self {
+        let loaded = get_index_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<SearchIndex>(&content).ok());
+
+        match loaded {
+            Some(index) if index.version == INDEX_VERSION => index,
+            _ => SearchIndex {
+                version: INDEX_VERSION,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Delete the index file so the next load starts empty
+    pub fn remove_file() -> Result<()> {
+        let path = get_index_path()?;
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("Failed to delete {:?}", path))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Write the index if anything changed since it was loaded
+    pub fn save(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let path = get_index_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string(self)?)
+            .with_context(|| format!("Failed to write {:?}", path))?;
+        self.dirty = false;
+        Ok(())
+    }
+
+    /// Whether the indexed entry still matches the session file on disk
+    pub fn is_fresh(&self, session: &Session) -> bool {
+        self.docs
+            .get(&key(session))
+            .is_some_and(|f| f.modified == session.modified && f.size == session.size_bytes)
+    }
+
+    /// (Re)index a session from its loaded `search_content`
+    pub fn update(&mut self, session: &Session) {
+        let Some(ref content) = session.search_content else {
+            return;
+        };
+        let key = key(session);
+        if self.is_fresh(session) {
+            // Moved but unchanged, e.g. its project directory was renamed
+            if let Some(file) = self.docs.get_mut(&key).filter(|f| f.path != session.path) {
+                file.path = session.path.clone();
+                self.dirty = true;
+            }
+            return;
+        }
+
+        if let Some(old) = self.docs.remove(&key) {
+            self.remove_postings(&HashSet::from([old.doc]));
+        }
+
+        let doc = self.next_doc;
+        self.next_doc += 1;
+        for term in tokenize(content) {
+            self.terms.entry(term).or_default().push(doc);
+        }
+        self.docs.insert(
+            key,
+            IndexedFile {
+                doc,
+                path: session.path.clone(),
+                modified: session.modified,
+                size: session.size_bytes,
+            },
+        );
+        self.dirty = true;
+    }
+
+    /// Drop entries for session files that no longer exist on disk
+    pub fn prune_missing(&mut self) {
+        let stale: HashSet<u32> = self
+            .docs
+            .iter()
+            .filter(|(_, f)| !f.path.exists())
+            .map(|(_, f)| f.doc)
+            .collect();
+        if stale.is_empty() {
+            return;
+        }
+
+        self.docs.retain(|_, f| !stale.contains(&f.doc));
+        self.remove_postings(&stale);
+        self.dirty = true;
+    }
+
+    /// Sessions that may contain `query_lower`, or `None` if the query has no indexable
+    /// terms. Sessions that aren't fresh in the index must be treated as candidates too.
+    pub fn candidates(&self, query_lower: &str) -> Option<Candidates> {
+        let query_terms = tokenize(query_lower);
+        if query_terms.is_empty() {
+            return None;
+        }
+
+        // Every query term must be a substring of some indexed term of the session
+        let mut docs: Option<HashSet<u32>> = None;
+        for query_term in &query_terms {
+            let matching: HashSet<u32> = self
+                .terms
+                .iter()
+                .filter(|(term, _)| term.contains(query_term.as_str()))
+                .flat_map(|(_, postings)| postings.iter().copied())
+                .collect();
+            docs = Some(match docs {
+                Some(prev) => prev.intersection(&matching).copied().collect(),
+                None => matching,
+            });
+        }
+
+        Some(Candidates {
+            docs: docs.unwrap_or_default(),
+        })
+    }
+
+    /// Whether `session` can match, given the candidates for a query
+    pub fn may_match(&self, session: &Session, candidates: &Candidates) -> bool {
+        match self.docs.get(&key(session)) {
+            Some(f) if self.is_fresh(session) => candidates.docs.contains(&f.doc),
+            _ => true,
+        }
+    }
+
+    fn remove_postings(&mut self, docs: &HashSet<u32>) {
+        self.terms.retain(|_, postings| {
+            postings.retain(|d| !docs.contains(d));
+            !postings.is_empty()
+        });
+    }
+}
+
+/// Document ids whose terms cover every term of a query
+#[derive(Debug)]
+pub struct Candidates {
+    docs: HashSet<u32>,
+}
+
+fn key(session: &Session) -> String {
+    session.id.clone()
+}
+
+/// Lowercase alphanumeric runs, deduplicated
+///
+/// Any alphanumeric run inside a query is a substring of a run in the matching content,
+/// which is what makes the index a safe prefilter for substring search.
+fn tokenize(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|t| t.chars().count() >= MIN_TERM_LEN)
+        .map(str::to_lowercase)
+        .filter(|t| seen.insert(t.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_session(id: &str, content: &str) -> Session {
+        Session {
+            size_bytes: content.len() as u64,
+            search_content: Some(content.to_lowercase()),
+            ..Session::test(id)
+        }
+    }
+
+    #[test]
+    fn test_candidates_narrow_by_substring_terms() {
+        let a = make_session("a", "Fix the parser bug");
+        let b = make_session("b", "write docs");
+        let mut index = SearchIndex::default();
+        index.update(&a);
+        index.update(&b);
+
+        let candidates = index.candidates("pars").unwrap();
+        assert!(index.may_match(&a, &candidates));
+        assert!(!index.may_match(&b, &candidates));
+
+        let candidates = index.candidates("the parser").unwrap();
+        assert!(index.may_match(&a, &candidates));
+
+        // Nothing indexable: no narrowing
+        assert!(index.candidates("a").is_none());
+    }
+
+    #[test]
+    fn test_changed_or_unknown_sessions_are_candidates() {
+        let a = make_session("a", "hello");
+        let mut index = SearchIndex::default();
+        index.update(&a);
+
+        let candidates = index.candidates("world").unwrap();
+        assert!(!index.may_match(&a, &candidates));
+
+        let mut changed = a.clone();
+        changed.size_bytes += 1;
+        assert!(index.may_match(&changed, &candidates));
+        assert!(index.may_match(&make_session("new", "x"), &candidates));
+    }
+
+    #[test]
+    fn test_moved_session_stays_indexed() {
+        let a = make_session("a", "hello");
+        let mut index = SearchIndex::default();
+        index.update(&a);
+        index.dirty = false;
+
+        let mut moved = a.clone();
+        moved.path = PathBuf::from("/tmp/renamed-proj/a.jsonl");
+        assert!(index.is_fresh(&moved));
+        index.update(&moved);
+        assert!(index.dirty);
+        assert_eq!(index.docs["a"].path, moved.path);
+
+        let candidates = index.candidates("world").unwrap();
+        assert!(!index.may_match(&moved, &candidates));
+    }
+
+    #[test]
+    fn test_reindex_and_prune_drop_old_terms() {
+        let a = make_session("a", "alpha");
+        let mut index = SearchIndex::default();
+        index.update(&a);
+
+        let mut changed = make_session("a", "beta");
+        changed.size_bytes += 1;
+        index.update(&changed);
+        assert!(!index.terms.contains_key("alpha"));
+        assert!(index.terms.contains_key("beta"));
+
+        // The test sessions don't exist on disk
+        index.prune_missing();
+        assert!(index.docs.is_empty());
+        assert!(index.terms.is_empty());
+    }
+}
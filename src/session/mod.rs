@@ -1,7 +1,19 @@
+pub mod bookmarks;
+pub mod diff;
+pub mod index;
 pub mod parser;
 pub mod scanner;
 pub mod types;
 
-pub use parser::{get_session_preview, load_session_messages, load_session_metadata};
-pub use scanner::{get_project_names, scan_sessions};
-pub use types::{DisplayMessage, MessageRole, Session};
+pub use bookmarks::{load_bookmarks, save_bookmarks};
+pub use diff::{
+    diff_user_messages, find_duplicate_sessions, merge_session_messages, shared_prefix_len,
+    user_message_lines, DiffOp,
+};
+pub use index::SearchIndex;
+pub use parser::{
+    get_session_preview, load_session_messages, load_session_metadata, validate_session_file,
+    DEFAULT_CHARS_PER_TOKEN,
+};
+pub use scanner::{default_projects_dir, get_project_names, resolve_session, scan_sessions};
+pub use types::{DisplayMessage, MessageRole, RenderOptions, Session};
@@ -1,31 +1,74 @@
 use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use flate2::read::MultiGzDecoder;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{self, BufRead, BufReader};
 use std::path::Path;
 
 use super::types::{
-    AssistantRecord, CustomTitleRecord, DisplayMessage, MessageRole, Session, SessionRecord,
-    SummaryRecord, UserRecord,
+    AssistantRecord, ContentBlock, CustomTitleRecord, DisplayMessage, MessageRole, RenderOptions,
+    Session, SessionRecord, SummaryRecord, UserRecord,
 };
 
+/// Characters per token used for token estimates unless configured otherwise
+pub const DEFAULT_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Message times seen while loading metadata: overall span and longest pause
+#[derive(Default)]
+struct Timeline {
+    first: Option<DateTime<Utc>>,
+    last: Option<DateTime<Utc>>,
+    longest_gap: Option<Duration>,
+}
+
+impl Timeline {
+    fn record(&mut self, time: DateTime<Utc>) {
+        if let Some(last) = self.last {
+            let gap = time - last;
+            self.longest_gap = Some(self.longest_gap.map_or(gap, |longest| longest.max(gap)));
+        }
+        self.first = Some(self.first.map_or(time, |first| first.min(time)));
+        self.last = Some(self.last.map_or(time, |last| last.max(time)));
+    }
+
+    fn duration(&self) -> Option<Duration> {
+        Some(self.last? - self.first?)
+    }
+}
+
 /// Load metadata from a session file (full scan for search indexing)
-pub fn load_session_metadata(session: &mut Session) -> Result<()> {
-    let file = File::open(&session.path)
-        .with_context(|| format!("Failed to open {:?}", session.path))?;
-    let reader = BufReader::new(file);
+///
+/// Token counts are estimates: content characters divided by `chars_per_token`.
+pub fn load_session_metadata(session: &mut Session, chars_per_token: f64) -> Result<()> {
+    let reader = open_session_file(&session.path)?;
 
     let mut first_timestamp = None;
     let mut first_user_message = None;
     let mut summary = None;
     let mut custom_title = None;
+    let mut cwd = None;
+    let mut git_branch = None;
     let mut message_count = 0;
+    let mut user_message_count = 0;
+    let mut assistant_message_count = 0;
+    let mut tool_use_count = 0;
     let mut all_content = Vec::new();
     let mut total_chars = 0usize;
+    let mut model_chars: BTreeMap<String, usize> = BTreeMap::new();
+    let mut parse_errors = 0;
+    let mut attachment_count = 0;
+    let mut has_code = false;
+    let mut system_records = 0;
+    let mut timeline = Timeline::default();
 
-    for line in reader.lines() {
+    for line in lossy_lines(reader) {
         let line = match line {
             Ok(l) => l,
-            Err(_) => continue,
+            Err(_) => {
+                parse_errors += 1;
+                continue;
+            }
         };
 
         if line.is_empty() {
@@ -34,7 +77,13 @@ pub fn load_session_metadata(session: &mut Session) -> Result<()> {
 
         let record: SessionRecord = match serde_json::from_str(&line) {
             Ok(r) => r,
-            Err(_) => continue,
+            Err(_) => {
+                // Records with an unfamiliar shape are expected; only count broken JSON
+                if is_malformed_json(&line) {
+                    parse_errors += 1;
+                }
+                continue;
+            }
         };
 
         match record {
@@ -49,14 +98,33 @@ pub fn load_session_metadata(session: &mut Session) -> Result<()> {
             SessionRecord::User(UserRecord {
                 timestamp,
                 message,
+                cwd: record_cwd,
+                git_branch: record_branch,
                 ..
             }) => {
-                message_count += 1;
+                // Injected system content isn't a real message
+                if !message.content.is_system_content() {
+                    message_count += 1;
+                    if !message.content.is_tool_result_only() {
+                        user_message_count += 1;
+                    }
+                } else {
+                    system_records += 1;
+                }
+                attachment_count += message.content.attachment_count();
+                timeline.record(timestamp);
                 if first_timestamp.is_none() {
                     first_timestamp = Some(timestamp);
                 }
+                if cwd.is_none() {
+                    cwd = record_cwd;
+                }
+                if let Some(branch) = record_branch.filter(|b| !b.is_empty()) {
+                    git_branch = Some(branch);
+                }
                 let text = message.content.as_text();
                 if !text.is_empty() {
+                    has_code |= text.contains("```");
                     all_content.push(text.clone());
                     total_chars += text.len();
                     if first_user_message.is_none() && !message.content.is_system_content() {
@@ -64,40 +132,75 @@ pub fn load_session_metadata(session: &mut Session) -> Result<()> {
                     }
                 }
             }
-            SessionRecord::Assistant(AssistantRecord { message, .. }) => {
+            SessionRecord::Assistant(AssistantRecord {
+                timestamp, message, ..
+            }) => {
+                timeline.record(timestamp);
                 message_count += 1;
+                assistant_message_count += 1;
+                tool_use_count += message
+                    .content
+                    .iter()
+                    .filter(|b| matches!(b, ContentBlock::ToolUse { .. }))
+                    .count();
+                attachment_count += message.content.iter().filter(|b| b.is_attachment()).count();
                 let text = message.as_text();
                 if !text.is_empty() {
+                    if let Some(model) = message.model {
+                        *model_chars.entry(model).or_insert(0) += text.len();
+                    }
+                    has_code |= text.contains("```");
                     all_content.push(text.clone());
                     total_chars += text.len();
                 }
             }
             SessionRecord::System(_) => {
                 // System records don't contain searchable content
+                system_records += 1;
             }
             _ => {}
         }
     }
 
     session.created = first_timestamp;
+    session.duration = timeline.duration();
+    session.longest_gap = timeline.longest_gap;
     session.summary = summary;
     session.first_message = first_user_message;
     session.custom_title = custom_title;
+    if let Some(cwd) = cwd {
+        session.project_path = cwd;
+    }
     session.message_count = Some(message_count);
+    session.user_message_count = user_message_count;
+    session.assistant_message_count = assistant_message_count;
+    session.tool_use_count = tool_use_count;
+    session.parse_errors = parse_errors;
+    session.attachment_count = attachment_count;
+    session.git_branch = git_branch;
+    session.has_code = has_code;
+    session.system_only = message_count == 0 && system_records > 0;
     session.search_content = Some(all_content.join(" ").to_lowercase());
-    // Rough token estimate: ~4 chars per token
-    session.token_count = Some(total_chars / 4);
+    session.token_count = Some(estimate_tokens(total_chars, chars_per_token));
+    session.model_tokens = model_chars
+        .into_iter()
+        .map(|(model, chars)| (model, estimate_tokens(chars, chars_per_token)))
+        .collect();
 
     Ok(())
 }
 
-/// Load all messages from a session file for preview
-pub fn load_session_messages(path: &Path) -> Result<Vec<DisplayMessage>> {
-    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
-    let reader = BufReader::new(file);
+/// Rough token count for `chars` characters of content
+fn estimate_tokens(chars: usize, chars_per_token: f64) -> usize {
+    (chars as f64 / chars_per_token).round() as usize
+}
+
+/// Load all messages from a session file, rendering content blocks with the given options
+pub fn load_session_messages(path: &Path, options: &RenderOptions) -> Result<Vec<DisplayMessage>> {
+    let reader = open_session_file(path)?;
     let mut messages = Vec::new();
 
-    for line in reader.lines() {
+    for line in lossy_lines(reader) {
         let line = match line {
             Ok(l) => l,
             Err(_) => continue,
@@ -118,7 +221,7 @@ pub fn load_session_messages(path: &Path) -> Result<Vec<DisplayMessage>> {
                 message,
                 ..
             }) => {
-                let content = message.content.as_text();
+                let content = message.content.render(options);
                 // Skip system injected content
                 if !message.content.is_system_content() && !content.is_empty() {
                     messages.push(DisplayMessage {
@@ -133,7 +236,7 @@ pub fn load_session_messages(path: &Path) -> Result<Vec<DisplayMessage>> {
                 message,
                 ..
             }) => {
-                let content = message.as_text();
+                let content = message.render(options);
                 if !content.is_empty() {
                     messages.push(DisplayMessage {
                         role: MessageRole::Assistant,
@@ -158,6 +261,109 @@ pub fn load_session_messages(path: &Path) -> Result<Vec<DisplayMessage>> {
     Ok(messages)
 }
 
+/// Problems found while validating a session file (line numbers are 1-based)
+#[derive(Debug, Default)]
+pub struct ValidationReport {
+    pub lines: usize,
+    /// Lines that aren't valid JSON (or aren't valid UTF-8)
+    pub malformed: Vec<usize>,
+    /// Lines with a known record type whose fields don't match the parser's schema
+    pub schema_errors: Vec<(usize, String)>,
+    /// Record types the parser doesn't know, with counts
+    pub unknown_types: BTreeMap<String, usize>,
+    /// user/assistant/system records without a timestamp
+    pub missing_timestamps: Vec<usize>,
+    /// Distinct `sessionId` values seen (more than one is inconsistent)
+    pub session_ids: BTreeSet<String>,
+}
+
+impl ValidationReport {
+    pub fn is_clean(&self) -> bool {
+        self.malformed.is_empty()
+            && self.schema_errors.is_empty()
+            && self.unknown_types.is_empty()
+            && self.missing_timestamps.is_empty()
+            && self.session_ids.len() <= 1
+    }
+}
+
+/// Parse every line of a session file and collect what `load_session_metadata` skips over
+pub fn validate_session_file(path: &Path) -> Result<ValidationReport> {
+    let reader = open_session_file(path)?;
+    let mut report = ValidationReport::default();
+
+    for (idx, line) in lossy_lines(reader).enumerate() {
+        let line_no = idx + 1;
+        report.lines = line_no;
+
+        let Ok(line) = line else {
+            report.malformed.push(line_no);
+            continue;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(&line) else {
+            report.malformed.push(line_no);
+            continue;
+        };
+
+        let record_type = value.get("type").and_then(|t| t.as_str()).unwrap_or("");
+        if let Some(id) = value.get("sessionId").and_then(|s| s.as_str()) {
+            report.session_ids.insert(id.to_string());
+        }
+        if matches!(record_type, "user" | "assistant" | "system")
+            && value.get("timestamp").map_or(true, |t| t.is_null())
+        {
+            report.missing_timestamps.push(line_no);
+        }
+
+        // Same deserialization load_session_metadata uses
+        match serde_json::from_str::<SessionRecord>(&line) {
+            Ok(SessionRecord::Unknown) => {
+                let name = if record_type.is_empty() { "(none)" } else { record_type };
+                *report.unknown_types.entry(name.to_string()).or_insert(0) += 1;
+            }
+            Ok(_) => {}
+            Err(e) => report.schema_errors.push((line_no, e.to_string())),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Split a reader into lines, replacing invalid UTF-8 with U+FFFD instead of failing
+///
+/// `BufRead::lines` rejects a whole line over one bad byte (e.g. pasted binary output),
+/// which would lose an otherwise valid record.
+fn lossy_lines<R: BufRead>(reader: R) -> impl Iterator<Item = io::Result<String>> {
+    reader.split(b'\n').map(|line| {
+        line.map(|mut bytes| {
+            if bytes.last() == Some(&b'\r') {
+                bytes.pop();
+            }
+            String::from_utf8(bytes)
+                .unwrap_or_else(|e| String::from_utf8_lossy(e.as_bytes()).into_owned())
+        })
+    })
+}
+
+/// Open a session file for reading, decompressing `.gz` files transparently
+fn open_session_file(path: &Path) -> Result<Box<dyn BufRead>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    if path.extension().is_some_and(|e| e == "gz") {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(file))))
+    } else {
+        Ok(Box::new(BufReader::new(file)))
+    }
+}
+
+/// Check whether a line is not valid JSON at all (truncated write, corruption)
+fn is_malformed_json(line: &str) -> bool {
+    serde_json::from_str::<serde::de::IgnoredAny>(line).is_err()
+}
+
 /// Truncate a message to a maximum length
 fn truncate_message(s: &str, max_chars: usize) -> String {
     let s = s.trim();
@@ -187,14 +393,16 @@ pub fn get_session_preview(session: &Session) -> String {
         return truncate_message(summary, 50);
     }
 
-    // Fallback: show message count if available
-    if let Some(count) = session.message_count {
-        if count > 0 {
-            return format!("[{} message{}]", count, if count == 1 { "" } else { "s" });
+    // Fallback: show message count once metadata is loaded
+    match session.message_count {
+        Some(0) => return "(empty)".to_string(),
+        Some(count) => {
+            return format!("[{} message{}]", count, if count == 1 { "" } else { "s" })
         }
+        None => {}
     }
 
-    // Last resort: show truncated session ID
+    // Last resort (metadata not loaded): show truncated session ID
     let short_id = if session.id.len() > 12 {
         format!("{}...", &session.id[..12])
     } else {
@@ -206,18 +414,47 @@ pub fn get_session_preview(session: &Session) -> String {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Utc;
     use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Session file in the temp dir, removed on drop
+    ///
+    /// Tests run in parallel in one process, so each file gets its own counter value.
+    struct TempSession(PathBuf);
+
+    impl TempSession {
+        fn new(suffix: &str) -> Self {
+            static NEXT: AtomicUsize = AtomicUsize::new(0);
+            let n = NEXT.fetch_add(1, Ordering::Relaxed);
+            let name = format!("ccsessionctl-test-{}-{}{}", std::process::id(), n, suffix);
+            TempSession(std::env::temp_dir().join(name))
+        }
+
+        fn with_contents(contents: impl AsRef<[u8]>) -> Self {
+            let file = TempSession::new(".jsonl");
+            file.write(contents);
+            file
+        }
+
+        fn write(&self, contents: impl AsRef<[u8]>) {
+            std::fs::write(&self.0, contents).unwrap();
+        }
 
-    fn make_test_session() -> Session {
-        Session::new(
-            "abc123def456".to_string(),
-            "test-project".to_string(),
-            "-home-user-test-project".to_string(),
-            PathBuf::from("/tmp/test.jsonl"),
-            1024,
-            Utc::now(),
-        )
+        /// Test session pointing at this file with its metadata loaded
+        fn load(&self) -> Session {
+            let mut session = Session {
+                path: self.0.clone(),
+                ..Session::test("abc")
+            };
+            load_session_metadata(&mut session, DEFAULT_CHARS_PER_TOKEN).unwrap();
+            session
+        }
+    }
+
+    impl Drop for TempSession {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
     }
 
     #[test]
@@ -227,6 +464,139 @@ mod tests {
         assert_eq!(truncate_message("  spaced  ", 20), "spaced");
     }
 
+    #[test]
+    fn test_invalid_utf8_line_still_parses() {
+        let mut bytes = br#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"binary "#.to_vec();
+        bytes.extend_from_slice(&[0xff, 0xfe]);
+        bytes.extend_from_slice(b" output\"}}\r\n");
+        let file = TempSession::with_contents(&bytes);
+
+        let messages = load_session_messages(&file.0, &RenderOptions::default()).unwrap();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].content.contains("binary \u{fffd}\u{fffd} output"));
+
+        let session = file.load();
+        assert_eq!(session.message_count, Some(1));
+        assert_eq!(session.parse_errors, 0);
+    }
+
+    #[test]
+    fn test_gzipped_session_parses() {
+        use flate2::write::GzEncoder;
+        use std::io::Write;
+
+        let file = TempSession::new(".jsonl.gz");
+        let mut encoder = GzEncoder::new(std::fs::File::create(&file.0).unwrap(), Default::default());
+        encoder
+            .write_all(
+                concat!(
+                    r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"compressed hello"}}"#,
+                    "\n",
+                    r#"{"type":"assistant","uuid":"2","timestamp":"2026-01-01T00:00:01Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"hi back"}]}}"#,
+                    "\n",
+                )
+                .as_bytes(),
+            )
+            .unwrap();
+        encoder.finish().unwrap();
+
+        let messages = load_session_messages(&file.0, &RenderOptions::default()).unwrap();
+        let session = file.load();
+        let report = validate_session_file(&file.0).unwrap();
+
+        assert_eq!(messages.len(), 2);
+        assert_eq!(messages[1].content, "hi back");
+        assert_eq!(session.message_count, Some(2));
+        assert_eq!(session.first_message.as_deref(), Some("compressed hello"));
+        assert!(report.is_clean());
+    }
+
+    #[test]
+    fn test_metadata_flags_system_only_sessions() {
+        let hook = r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T09:00:00Z","sessionId":"a","message":{"role":"user","content":"<system-reminder>hook output</system-reminder>"}}"#;
+        let file = TempSession::with_contents(format!("{}\n", hook));
+        assert!(file.load().system_only);
+
+        let reply = r#"{"type":"assistant","uuid":"2","timestamp":"2026-01-01T09:00:01Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"ok"}]}}"#;
+        file.write(format!("{}\n{}\n", hook, reply));
+        assert!(!file.load().system_only);
+    }
+
+    #[test]
+    fn test_metadata_records_duration_and_longest_gap() {
+        let file = TempSession::with_contents(concat!(
+            r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T09:00:00Z","sessionId":"a","message":{"role":"user","content":"start"}}"#,
+            "\n",
+            r#"{"type":"assistant","uuid":"2","timestamp":"2026-01-01T09:05:00Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"ok"}]}}"#,
+            "\n",
+            r#"{"type":"user","uuid":"3","timestamp":"2026-01-02T10:05:00Z","sessionId":"a","message":{"role":"user","content":"back again"}}"#,
+            "\n",
+            r#"{"type":"assistant","uuid":"4","timestamp":"2026-01-02T10:06:00Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"hi"}]}}"#,
+            "\n",
+        ));
+
+        let session = file.load();
+
+        assert_eq!(session.duration, Some(Duration::minutes(25 * 60 + 6)));
+        assert_eq!(session.longest_gap, Some(Duration::hours(25)));
+    }
+
+    #[test]
+    fn test_estimate_tokens_uses_ratio() {
+        assert_eq!(estimate_tokens(400, DEFAULT_CHARS_PER_TOKEN), 100);
+        assert_eq!(estimate_tokens(400, 3.0), 133);
+        assert_eq!(estimate_tokens(0, 2.5), 0);
+    }
+
+    #[test]
+    fn test_is_malformed_json() {
+        assert!(is_malformed_json("not json"));
+        assert!(is_malformed_json(r#"{"type":"user","uuid":"#));
+        assert!(!is_malformed_json(r#"{"type":"user","unexpected":true}"#));
+    }
+
+    #[test]
+    fn test_validate_session_file() {
+        let file = TempSession::with_contents(concat!(
+            r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"hi"}}"#,
+            "\n",
+            "not json\n",
+            r#"{"type":"brand-new","sessionId":"b"}"#,
+            "\n",
+            r#"{"type":"user","uuid":"2","sessionId":"a","message":{"role":"user","content":"hi"}}"#,
+            "\n",
+        ));
+
+        let report = validate_session_file(&file.0).unwrap();
+
+        assert_eq!(report.lines, 4);
+        assert_eq!(report.malformed, vec![2]);
+        assert_eq!(report.unknown_types.get("brand-new"), Some(&1));
+        assert_eq!(report.missing_timestamps, vec![4]);
+        assert_eq!(report.schema_errors.len(), 1);
+        assert_eq!(report.session_ids.len(), 2);
+        assert!(!report.is_clean());
+    }
+
+    #[test]
+    fn test_validate_reports_records_missing_schema_fields() {
+        let file = TempSession::with_contents(concat!(
+            r#"{"type":"user","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"no uuid"}}"#,
+            "\n",
+            r#"{"type":"assistant","uuid":"2","timestamp":"2026-01-01T00:00:01Z","sessionId":"a","message":{"content":[]}}"#,
+            "\n",
+            r#"{"type":"file-history-snapshot","snapshot":{}}"#,
+            "\n",
+            r#"{"type":"user","uuid":"3","timestamp":"2026-01-01T00:00:02Z","sessionId":"a","message":{"role":"user","content":"ok"}}"#,
+            "\n",
+        ));
+
+        let report = validate_session_file(&file.0).unwrap();
+        let lines: Vec<usize> = report.schema_errors.iter().map(|(n, _)| *n).collect();
+        assert_eq!(lines, vec![1, 2, 3]);
+        assert_eq!(file.load().message_count, Some(1));
+    }
+
     #[test]
     fn test_truncate_with_newlines() {
         assert_eq!(truncate_message("line1\nline2", 20), "line1 line2");
@@ -234,7 +604,7 @@ mod tests {
 
     #[test]
     fn test_preview_custom_title_priority() {
-        let mut session = make_test_session();
+        let mut session = Session::test("abc");
         session.custom_title = Some("My Custom Title".to_string());
         session.summary = Some("Summary text".to_string());
         session.first_message = Some("First message".to_string());
@@ -244,7 +614,7 @@ mod tests {
     #[test]
     fn test_preview_first_message_priority() {
         // First message now takes priority over summary
-        let mut session = make_test_session();
+        let mut session = Session::test("abc");
         session.summary = Some("Summary text".to_string());
         session.first_message = Some("First message".to_string());
         assert_eq!(get_session_preview(&session), "First message");
@@ -253,34 +623,115 @@ mod tests {
     #[test]
     fn test_preview_summary_fallback() {
         // Summary is used when no first_message
-        let mut session = make_test_session();
+        let mut session = Session::test("abc");
         session.summary = Some("Summary text".to_string());
         assert_eq!(get_session_preview(&session), "Summary text");
     }
 
     #[test]
     fn test_preview_message_count_fallback() {
-        let mut session = make_test_session();
+        let mut session = Session::test("abc");
         session.message_count = Some(5);
         assert_eq!(get_session_preview(&session), "[5 messages]");
     }
 
     #[test]
     fn test_preview_message_count_singular() {
-        let mut session = make_test_session();
+        let mut session = Session::test("abc");
         session.message_count = Some(1);
         assert_eq!(get_session_preview(&session), "[1 message]");
     }
 
+    #[test]
+    fn test_preview_empty_session() {
+        let mut session = Session::test("abc");
+        session.message_count = Some(0);
+        assert!(session.is_empty());
+        assert_eq!(get_session_preview(&session), "(empty)");
+    }
+
+    #[test]
+    fn test_system_only_session_is_empty() {
+        let file = TempSession::with_contents(concat!(
+            r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"<system-reminder>ctx</system-reminder>"}}"#,
+            "\n",
+            r#"{"type":"system","uuid":"2","timestamp":"2026-01-01T00:00:01Z","content":"hook ran"}"#,
+            "\n",
+        ));
+
+        let session = file.load();
+
+        assert_eq!(session.message_count, Some(0));
+        assert!(session.is_empty());
+        assert_eq!(get_session_preview(&session), "(empty)");
+    }
+
+    #[test]
+    fn test_git_branch_tracks_latest_user_message() {
+        let file = TempSession::with_contents(concat!(
+            r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","gitBranch":"main","message":{"role":"user","content":"start"}}"#,
+            "\n",
+            r#"{"type":"user","uuid":"2","timestamp":"2026-01-01T00:00:01Z","sessionId":"a","gitBranch":"feature/x","message":{"role":"user","content":"switch"}}"#,
+            "\n",
+            r#"{"type":"user","uuid":"3","timestamp":"2026-01-01T00:00:02Z","sessionId":"a","gitBranch":"","message":{"role":"user","content":"detached"}}"#,
+            "\n",
+        ));
+
+        let session = file.load();
+
+        assert_eq!(session.git_branch.as_deref(), Some("feature/x"));
+    }
+
+    #[test]
+    fn test_role_counts_exclude_tool_results() {
+        let file = TempSession::with_contents(concat!(
+            r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"list files"}}"#,
+            "\n",
+            r#"{"type":"assistant","uuid":"2","timestamp":"2026-01-01T00:00:01Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"sure"},{"type":"tool_use","id":"t1","name":"Bash","input":{"command":"ls"}}]}}"#,
+            "\n",
+            r#"{"type":"user","uuid":"3","timestamp":"2026-01-01T00:00:02Z","sessionId":"a","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"t1","content":"a.txt"}]}}"#,
+            "\n",
+            r#"{"type":"assistant","uuid":"4","timestamp":"2026-01-01T00:00:03Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"one file"}]}}"#,
+            "\n",
+        ));
+
+        let session = file.load();
+
+        assert_eq!(session.message_count, Some(4));
+        assert_eq!(session.user_message_count, 1);
+        assert_eq!(session.assistant_message_count, 2);
+        assert_eq!(session.tool_use_count, 1);
+    }
+
+    #[test]
+    fn test_has_code_detects_fenced_blocks() {
+        let user = r#"{"type":"user","uuid":"1","timestamp":"2026-01-01T00:00:00Z","sessionId":"a","message":{"role":"user","content":"write a function"}}"#;
+        let reply = r#"{"type":"assistant","uuid":"2","timestamp":"2026-01-01T00:00:01Z","sessionId":"a","message":{"role":"assistant","content":[{"type":"text","text":"```rust\nfn f() {}\n```"}]}}"#;
+
+        let file = TempSession::with_contents(format!("{}\n", user));
+        assert!(!file.load().has_code);
+
+        file.write(format!("{}\n{}\n", user, reply));
+        assert!(file.load().has_code);
+    }
+
+    #[test]
+    fn test_session_with_message_is_not_empty() {
+        let mut session = Session::test("abc");
+        session.message_count = Some(1);
+        session.first_message = Some("hi".to_string());
+        assert!(!session.is_empty());
+    }
+
     #[test]
     fn test_preview_session_id_fallback() {
-        let session = make_test_session();
+        let session = Session::test("abc123def456");
         assert_eq!(get_session_preview(&session), "[abc123def456]");
     }
 
     #[test]
     fn test_preview_long_session_id_truncated() {
-        let mut session = make_test_session();
+        let mut session = Session::test("abc");
         session.id = "abcdefghijklmnopqrstuvwxyz".to_string();
         assert_eq!(get_session_preview(&session), "[abcdefghijkl...]");
     }
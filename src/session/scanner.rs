@@ -1,26 +1,49 @@
-use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use chrono::{DateTime, Utc};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use super::types::{Project, Session};
+use super::types::{session_id_from_file_name, Project, Session};
 
-/// Discover all Claude Code sessions from ~/.claude/projects/
-pub fn scan_sessions() -> Result<Vec<Session>> {
-    let projects_dir = get_projects_dir()?;
-    let mut sessions = Vec::new();
+/// Sessions found by a scan, plus the project directories that had to be skipped
+#[derive(Debug, Default)]
+pub struct ScanResult {
+    pub sessions: Vec<Session>,
+    /// "<path>: <reason>" for each project directory that couldn't be read
+    pub skipped: Vec<String>,
+}
+
+/// Discover all Claude Code sessions under a projects directory
+///
+/// A missing projects directory yields no sessions; one that can't be read is an error.
+/// Unreadable project directories are skipped and listed in the result.
+/// With `follow_symlinks` off, symlinked project directories and session files are skipped.
+pub fn scan_sessions(projects_dir: &Path, follow_symlinks: bool) -> Result<ScanResult> {
+    let mut result = ScanResult::default();
+    let mut projects = Vec::new();
 
     if !projects_dir.exists() {
-        return Ok(sessions);
+        return Ok(result);
+    }
+    if !projects_dir.is_dir() {
+        bail!("projects directory is not a directory: {}", projects_dir.display());
     }
 
-    for entry in fs::read_dir(&projects_dir)
-        .with_context(|| format!("Failed to read {:?}", projects_dir))?
-    {
-        let entry = entry?;
+    let entries = fs::read_dir(projects_dir).map_err(|e| {
+        anyhow!(
+            "projects directory exists but can't be read: {}: {}",
+            projects_dir.display(),
+            e
+        )
+    })?;
+    for entry in entries {
+        let Ok(entry) = entry else {
+            continue;
+        };
         let path = entry.path();
 
-        if !path.is_dir() {
+        if !path.is_dir() || (!follow_symlinks && is_symlink(&entry)) {
             continue;
         }
 
@@ -35,51 +58,105 @@ pub fn scan_sessions() -> Result<Vec<Session>> {
             continue;
         }
 
-        let project = Project::from_dir_name(&dir_name, path.clone());
-        let project_sessions = scan_project_sessions(&project)?;
-        sessions.extend(project_sessions);
+        projects.push(Project::from_dir_name(&dir_name, path.clone()));
+    }
+
+    disambiguate_project_names(&mut projects);
+    for project in &projects {
+        match scan_project_sessions(project, follow_symlinks) {
+            Ok(sessions) => result.sessions.extend(sessions),
+            Err(e) => result
+                .skipped
+                .push(format!("{}: {}", project.path.display(), e)),
+        }
     }
 
     // Sort by modification time (newest first)
-    sessions.sort_by(|a, b| b.modified.cmp(&a.modified));
+    result.sessions.sort_by_key(|s| std::cmp::Reverse(s.modified));
 
-    Ok(sessions)
+    Ok(result)
+}
+
+/// Give projects from different directories that share a display name distinct names
+///
+/// "foo" under /home/a and /home/b become "a/foo" and "b/foo"; if the parents also
+/// collide, the full decoded path is used.
+fn disambiguate_project_names(projects: &mut [Project]) {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for project in projects.iter() {
+        *counts.entry(project.name.clone()).or_default() += 1;
+    }
+
+    let with_parent = |project: &Project| {
+        let decoded = Project::decode_path(&project.raw_name);
+        let mut parts = decoded.rsplit('/').filter(|s| !s.is_empty());
+        let name = parts.next().unwrap_or_default();
+        match parts.next() {
+            Some(parent) => format!("{}/{}", parent, name),
+            None => decoded.clone(),
+        }
+    };
+
+    let mut parent_counts: HashMap<String, usize> = HashMap::new();
+    for project in projects.iter().filter(|p| counts[&p.name] > 1) {
+        *parent_counts.entry(with_parent(project)).or_default() += 1;
+    }
+
+    for project in projects.iter_mut().filter(|p| counts[&p.name] > 1) {
+        let candidate = with_parent(project);
+        project.name = if parent_counts[&candidate] > 1 {
+            Project::decode_path(&project.raw_name)
+        } else {
+            candidate
+        };
+    }
 }
 
-/// Get the Claude Code projects directory
-fn get_projects_dir() -> Result<PathBuf> {
+/// Get the default Claude Code projects directory (~/.claude/projects)
+pub fn default_projects_dir() -> Result<PathBuf> {
     let home = dirs::home_dir().context("Could not find home directory")?;
     Ok(home.join(".claude").join("projects"))
 }
 
+/// Whether a directory entry is itself a symlink (not followed)
+fn is_symlink(entry: &fs::DirEntry) -> bool {
+    entry.file_type().is_ok_and(|ft| ft.is_symlink())
+}
+
 /// Scan sessions within a project directory
-fn scan_project_sessions(project: &Project) -> Result<Vec<Session>> {
+fn scan_project_sessions(project: &Project, follow_symlinks: bool) -> Result<Vec<Session>> {
     let mut sessions = Vec::new();
 
     for entry in fs::read_dir(&project.path)? {
-        let entry = entry?;
+        let Ok(entry) = entry else {
+            continue;
+        };
         let path = entry.path();
 
-        // Only look at .jsonl files
-        if path.extension().and_then(|e| e.to_str()) != Some("jsonl") {
+        // Only look at .jsonl and .jsonl.gz files
+        let Some(session_id) = path
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(session_id_from_file_name)
+            .map(str::to_string)
+        else {
+            continue;
+        };
+        if !follow_symlinks && is_symlink(&entry) {
             continue;
         }
 
-        let session_id = path
-            .file_stem()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        // Get file metadata
-        let metadata = fs::metadata(&path)?;
+        // Get file metadata (a dangling symlink has none)
+        let Ok(metadata) = fs::metadata(&path) else {
+            continue;
+        };
         let size_bytes = metadata.len();
         let modified: DateTime<Utc> = metadata
             .modified()
             .map(|t| t.into())
             .unwrap_or_else(|_| Utc::now());
 
-        let session = Session::new(
+        let mut session = Session::new(
             session_id,
             project.name.clone(),
             project.raw_name.clone(),
@@ -87,6 +164,9 @@ fn scan_project_sessions(project: &Project) -> Result<Vec<Session>> {
             size_bytes,
             modified,
         );
+        if session.has_directory {
+            (session.dir_file_count, session.dir_size_bytes) = dir_usage(&session.sidecar_dir());
+        }
 
         sessions.push(session);
     }
@@ -94,6 +174,22 @@ fn scan_project_sessions(project: &Project) -> Result<Vec<Session>> {
     Ok(sessions)
 }
 
+/// Count and sum the sizes of all files under a directory (unreadable entries are skipped)
+pub fn dir_usage(path: &Path) -> (usize, u64) {
+    let Ok(entries) = fs::read_dir(path) else {
+        return (0, 0);
+    };
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| match entry.file_type() {
+            Ok(ft) if ft.is_dir() => dir_usage(&entry.path()),
+            Ok(ft) if ft.is_file() => (1, entry.metadata().map(|m| m.len()).unwrap_or(0)),
+            _ => (0, 0),
+        })
+        .fold((0, 0), |(files, bytes), (f, b)| (files + f, bytes + b))
+}
+
 /// Get all unique project names from sessions
 pub fn get_project_names(sessions: &[Session]) -> Vec<String> {
     let mut names: Vec<String> = sessions
@@ -105,10 +201,92 @@ pub fn get_project_names(sessions: &[Session]) -> Vec<String> {
     names
 }
 
+/// Resolve a session by full id or unique id prefix
+pub fn resolve_session<'a>(
+    sessions: impl IntoIterator<Item = &'a Session>,
+    id_or_prefix: &str,
+) -> Result<&'a Session> {
+    let sessions: Vec<&Session> = sessions.into_iter().collect();
+    if let Some(exact) = sessions.iter().find(|s| s.id == id_or_prefix) {
+        return Ok(exact);
+    }
+
+    let matches: Vec<&Session> = sessions
+        .into_iter()
+        .filter(|s| s.id.starts_with(id_or_prefix))
+        .collect();
+
+    match matches.as_slice() {
+        [] => bail!("No session matches '{}'", id_or_prefix),
+        [session] => Ok(session),
+        _ => {
+            let ids: Vec<&str> = matches.iter().take(5).map(|s| s.id.as_str()).collect();
+            bail!(
+                "'{}' is ambiguous: matches {} sessions ({}{})",
+                id_or_prefix,
+                matches.len(),
+                ids.join(", "),
+                if matches.len() > 5 { ", ..." } else { "" }
+            )
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_dir_usage_recurses() {
+        let root = std::env::temp_dir().join(format!("ccsessionctl-dirsize-{}", std::process::id()));
+        fs::create_dir_all(root.join("nested")).unwrap();
+        fs::write(root.join("a.txt"), "12345").unwrap();
+        fs::write(root.join("nested").join("b.txt"), "123").unwrap();
+
+        assert_eq!(dir_usage(&root), (2, 8));
+        assert_eq!(dir_usage(&root.join("missing")), (0, 0));
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn test_scan_reports_projects_dir_that_is_a_file() {
+        let file = std::env::temp_dir().join(format!("ccsessionctl-scan-{}", std::process::id()));
+        fs::write(&file, "").unwrap();
+
+        let err = scan_sessions(&file, true).unwrap_err().to_string();
+        assert!(err.starts_with("projects directory is not a directory"), "{}", err);
+        assert!(scan_sessions(&file.with_extension("missing"), true)
+            .unwrap()
+            .sessions
+            .is_empty());
+
+        fs::remove_file(&file).unwrap();
+    }
+
+    #[test]
+    fn test_resolve_session_prefix() {
+        let sessions = vec![Session::test("abc123"), Session::test("abd456")];
+        assert_eq!(resolve_session(&sessions, "abc").unwrap().id, "abc123");
+        assert!(resolve_session(&sessions, "ab").is_err());
+        assert!(resolve_session(&sessions, "zzz").is_err());
+    }
+
+    #[test]
+    fn test_resolve_session_exact_beats_prefix() {
+        let sessions = vec![Session::test("abc"), Session::test("abcdef")];
+        assert_eq!(resolve_session(&sessions, "abc").unwrap().id, "abc");
+    }
+
+    #[test]
+    fn test_resolve_session_across_lists() {
+        let listed = [Session::test("abc123")];
+        let agents = [Session::test("agent-9f"), Session::test("abd456")];
+        let all = || listed.iter().chain(&agents);
+        assert_eq!(resolve_session(all(), "agent").unwrap().id, "agent-9f");
+        assert!(resolve_session(all(), "ab").is_err());
+    }
+
     #[test]
     fn test_project_from_dir_name() {
         let project = Project::from_dir_name(
@@ -119,9 +297,27 @@ mod tests {
         assert_eq!(project.raw_name, "-home-pknull-Projects-threshold");
     }
 
+    #[test]
+    fn test_disambiguate_project_names() {
+        let mut projects = vec![
+            Project::from_dir_name("-home-a-foo", PathBuf::from("/a")),
+            Project::from_dir_name("-home-b-foo", PathBuf::from("/b")),
+            Project::from_dir_name("-home-a-bar", PathBuf::from("/c")),
+        ];
+        disambiguate_project_names(&mut projects);
+
+        let names: Vec<&str> = projects.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["a/foo", "b/foo", "bar"]);
+    }
+
     #[test]
     fn test_project_from_simple_name() {
         let project = Project::from_dir_name("myproject", PathBuf::from("/test"));
         assert_eq!(project.name, "myproject");
     }
+
+    #[test]
+    fn test_decode_path() {
+        assert_eq!(Project::decode_path("-tmp-scratch"), "/tmp/scratch");
+    }
 }
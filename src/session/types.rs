@@ -1,6 +1,20 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::utils::Symbols;
+
+/// File name suffixes of session files, longest first
+pub const SESSION_SUFFIXES: &[&str] = &[".jsonl.gz", ".jsonl"];
+
+/// Session id encoded in a session file name (`<id>.jsonl` or gzipped `<id>.jsonl.gz`)
+pub fn session_id_from_file_name(name: &str) -> Option<&str> {
+    SESSION_SUFFIXES
+        .iter()
+        .find_map(|suffix| name.strip_suffix(suffix))
+        .filter(|id| !id.is_empty())
+}
 
 /// A Claude Code project (directory under ~/.claude/projects/)
 #[derive(Debug, Clone)]
@@ -14,9 +28,10 @@ impl Project {
     /// Parse project name from directory name
     /// e.g., "-home-pknull-Projects-threshold" -> "threshold"
     pub fn from_dir_name(raw_name: &str, path: PathBuf) -> Self {
-        let name = raw_name
-            .rsplit('-')
-            .next()
+        let decoded = Self::decode_path(raw_name);
+        let name = decoded
+            .rsplit('/')
+            .find(|seg| !seg.is_empty())
             .unwrap_or(raw_name)
             .to_string();
 
@@ -26,6 +41,15 @@ impl Project {
             raw_name: raw_name.to_string(),
         }
     }
+
+    /// Decode the original working directory from the encoded directory name
+    /// e.g., "-home-pknull-Projects-threshold" -> "/home/pknull/Projects/threshold"
+    ///
+    /// Claude replaces `/` with `-`, so dashes in directory names can't be recovered.
+    /// Sessions prefer the `cwd` recorded in their messages once metadata is loaded.
+    pub fn decode_path(raw_name: &str) -> String {
+        format!("/{}", raw_name.trim_start_matches('-').replace('-', "/"))
+    }
 }
 
 /// A session file with metadata
@@ -34,21 +58,49 @@ pub struct Session {
     pub id: String,
     pub project: String,
     pub project_raw: String,
+    /// Working directory the session was started in
+    pub project_path: String,
     pub path: PathBuf,
     pub size_bytes: u64,
     pub modified: DateTime<Utc>,
     pub created: Option<DateTime<Utc>>,
+    /// Time from the first to the last message
+    pub duration: Option<Duration>,
+    /// Longest pause between two consecutive messages
+    pub longest_gap: Option<Duration>,
     pub summary: Option<String>,
     pub first_message: Option<String>,
     pub message_count: Option<usize>,
+    /// Messages typed by the user (tool results and injected content excluded)
+    pub user_message_count: usize,
+    /// Assistant replies
+    pub assistant_message_count: usize,
+    /// Tool calls made in assistant replies
+    pub tool_use_count: usize,
     pub is_agent: bool,
     pub has_directory: bool,
+    /// Total size of files in the sidecar directory (0 if none)
+    pub dir_size_bytes: u64,
+    /// Number of files in the sidecar directory (0 if none)
+    pub dir_file_count: usize,
     /// User-provided session name via /rename command
     pub custom_title: Option<String>,
     /// Full searchable content (all messages concatenated)
     pub search_content: Option<String>,
     /// Token count estimate
     pub token_count: Option<usize>,
+    /// Token estimate per assistant model name
+    pub model_tokens: BTreeMap<String, usize>,
+    /// Lines that could not be read or were not valid JSON
+    pub parse_errors: usize,
+    /// Image and document blocks across all messages
+    pub attachment_count: usize,
+    /// Git branch recorded on the most recent user message
+    pub git_branch: Option<String>,
+    /// Whether any message contains a fenced (```) code block
+    pub has_code: bool,
+    /// Only system/meta records (hook output, injected context) and no real messages
+    pub system_only: bool,
 }
 
 impl Session {
@@ -61,27 +113,99 @@ impl Session {
         modified: DateTime<Utc>,
     ) -> Self {
         let is_agent = id.starts_with("agent-");
-        let dir_path = path.with_extension("");
-        let has_directory = dir_path.is_dir();
+        let has_directory = sidecar_path(&path).is_dir();
+        let project_path = Project::decode_path(&project_raw);
 
         Self {
             id,
             project,
+            project_path,
             project_raw,
             path,
             size_bytes,
             modified,
             created: None,
+            duration: None,
+            longest_gap: None,
             summary: None,
             first_message: None,
             message_count: None,
+            user_message_count: 0,
+            assistant_message_count: 0,
+            tool_use_count: 0,
             is_agent,
             has_directory,
+            dir_size_bytes: 0,
+            dir_file_count: 0,
             custom_title: None,
             search_content: None,
             token_count: None,
+            model_tokens: BTreeMap::new(),
+            parse_errors: 0,
+            attachment_count: 0,
+            git_branch: None,
+            has_code: false,
+            system_only: false,
         }
     }
+
+    /// Take over the location (path, project, sidecar directory) of a rescanned copy,
+    /// keeping parsed metadata; used when a project directory was renamed
+    pub fn relocate(&mut self, scanned: &Session) {
+        self.path = scanned.path.clone();
+        self.project = scanned.project.clone();
+        self.project_raw = scanned.project_raw.clone();
+        self.project_path = scanned.project_path.clone();
+        self.has_directory = scanned.has_directory;
+        self.dir_size_bytes = scanned.dir_size_bytes;
+        self.dir_file_count = scanned.dir_file_count;
+    }
+
+    /// Sidecar directory next to the session file (`<id>/`), which may not exist
+    pub fn sidecar_dir(&self) -> PathBuf {
+        sidecar_path(&self.path)
+    }
+
+    /// Whether the session file is gzip-compressed (`.jsonl.gz`)
+    pub fn is_compressed(&self) -> bool {
+        self.path.extension().is_some_and(|e| e == "gz")
+    }
+
+    /// Whether the session has no content worth keeping (a `--prune-empty` candidate)
+    ///
+    /// System-only sessions count as empty because injected system content isn't
+    /// included in `message_count`. Unloaded metadata (`None`) also counts, so callers
+    /// should skip sessions whose metadata failed to load.
+    pub fn is_empty(&self) -> bool {
+        self.message_count.unwrap_or(0) == 0
+            && self.summary.is_none()
+            && self.first_message.is_none()
+            && self.custom_title.is_none()
+    }
+}
+
+#[cfg(test)]
+impl Session {
+    /// Empty session `<id>` in project "proj" at `/tmp/proj/<id>.jsonl`, modified now
+    pub fn test(id: &str) -> Self {
+        Session::new(
+            id.to_string(),
+            "proj".to_string(),
+            "-tmp-proj".to_string(),
+            PathBuf::from(format!("/tmp/proj/{}.jsonl", id)),
+            0,
+            Utc::now(),
+        )
+    }
+}
+
+/// `<dir>/<id>` for `<dir>/<id>.jsonl` or `<dir>/<id>.jsonl.gz`
+fn sidecar_path(path: &Path) -> PathBuf {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    match session_id_from_file_name(name) {
+        Some(id) => path.with_file_name(id),
+        None => path.with_extension(""),
+    }
 }
 
 /// JSONL record types from Claude Code sessions
@@ -90,11 +214,17 @@ impl Session {
 pub enum SessionRecord {
     Summary(SummaryRecord),
     CustomTitle(CustomTitleRecord),
-    FileHistorySnapshot(FileHistorySnapshot),
+    FileHistorySnapshot {
+        #[serde(flatten)]
+        _snapshot: FileHistorySnapshot,
+    },
     User(UserRecord),
     Assistant(AssistantRecord),
     System(SystemRecord),
-    QueueOperation(QueueOperationRecord),
+    QueueOperation {
+        #[serde(flatten)]
+        _operation: QueueOperationRecord,
+    },
     #[serde(other)]
     Unknown,
 }
@@ -103,7 +233,7 @@ pub enum SessionRecord {
 pub struct SummaryRecord {
     pub summary: String,
     #[serde(rename = "leafUuid")]
-    pub leaf_uuid: Option<String>,
+    pub _leaf_uuid: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -115,49 +245,53 @@ pub struct CustomTitleRecord {
 #[derive(Debug, Clone, Deserialize)]
 pub struct FileHistorySnapshot {
     #[serde(rename = "messageId")]
-    pub message_id: String,
+    pub _message_id: String,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct UserRecord {
-    pub uuid: String,
+    #[serde(rename = "uuid")]
+    pub _uuid: String,
     pub timestamp: DateTime<Utc>,
     #[serde(rename = "sessionId")]
-    pub session_id: String,
+    pub _session_id: String,
     pub message: Message,
     pub cwd: Option<String>,
     #[serde(rename = "gitBranch")]
     pub git_branch: Option<String>,
     #[serde(rename = "isMeta")]
-    pub is_meta: Option<bool>,
+    pub _is_meta: Option<bool>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssistantRecord {
-    pub uuid: String,
+    #[serde(rename = "uuid")]
+    pub _uuid: String,
     pub timestamp: DateTime<Utc>,
     #[serde(rename = "sessionId")]
-    pub session_id: String,
+    pub _session_id: String,
     pub message: AssistantMessage,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct SystemRecord {
-    pub uuid: Option<String>,
+    #[serde(rename = "uuid")]
+    pub _uuid: Option<String>,
     pub timestamp: Option<DateTime<Utc>>,
     #[serde(rename = "sessionId")]
-    pub session_id: Option<String>,
+    pub _session_id: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct QueueOperationRecord {
     #[serde(rename = "queueOperations")]
-    pub queue_operations: Option<serde_json::Value>,
+    pub _queue_operations: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Message {
-    pub role: String,
+    #[serde(rename = "role")]
+    pub _role: String,
     pub content: MessageContent,
 }
 
@@ -168,21 +302,112 @@ pub enum MessageContent {
     Structured(Vec<ContentBlock>),
 }
 
+/// Characters of a tool call's command/pattern/path shown before it is cut off
+const TOOL_INPUT_CHARS: usize = 60;
+/// Characters of a tool result shown before it is cut off
+const TOOL_RESULT_CHARS: usize = 200;
+
+/// Controls how content blocks are rendered to text
+#[derive(Debug, Clone, Copy)]
+pub struct RenderOptions {
+    /// Render tool calls as `[tool: name args]` markers instead of emoji
+    pub plain: bool,
+    /// Include thinking blocks
+    pub thinking: bool,
+    /// Include tool calls and tool results
+    pub tools: bool,
+    /// Remove ANSI escape codes (terminal colors) from text and tool output
+    pub strip_ansi: bool,
+    /// Show timestamps in the system time zone instead of UTC
+    pub local_time: bool,
+    /// Show tool inputs and results in full instead of cutting them short
+    pub full_tools: bool,
+    /// ASCII markers (`[tool]`, `[think]`, `[result]`) instead of emoji
+    pub ascii: bool,
+}
+
+impl RenderOptions {
+    /// Markers for this mode (emoji or ASCII)
+    pub fn symbols(&self) -> Symbols {
+        Symbols::new(self.ascii)
+    }
+
+    /// Text as it should be shown, with escape codes removed unless they're kept
+    fn clean(&self, text: &str) -> String {
+        if self.strip_ansi {
+            crate::utils::strip_ansi(text)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Tool input or output, cut to `max_chars` unless full tool output is shown
+    fn tool_text(&self, text: &str, max_chars: usize) -> String {
+        if self.full_tools {
+            text.to_string()
+        } else {
+            ContentBlock::truncate_result(text, max_chars)
+        }
+    }
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            plain: false,
+            thinking: true,
+            tools: true,
+            strip_ansi: true,
+            local_time: false,
+            full_tools: false,
+            ascii: false,
+        }
+    }
+}
+
 impl MessageContent {
     /// Extract plain text from message content
     pub fn as_text(&self) -> String {
+        self.render(&RenderOptions::default())
+    }
+
+    /// Render message content with the given options
+    pub fn render(&self, options: &RenderOptions) -> String {
         match self {
-            MessageContent::Text(s) => s.clone(),
+            MessageContent::Text(s) => options.clean(s),
             MessageContent::Structured(blocks) => {
                 blocks
                     .iter()
-                    .filter_map(|b| b.as_text())
+                    .filter_map(|b| b.render(options))
                     .collect::<Vec<_>>()
                     .join("\n")
             }
         }
     }
 
+    /// Number of image and document blocks
+    pub fn attachment_count(&self) -> usize {
+        match self {
+            MessageContent::Text(_) => 0,
+            MessageContent::Structured(blocks) => {
+                blocks.iter().filter(|b| b.is_attachment()).count()
+            }
+        }
+    }
+
+    /// Whether the content only carries tool results (sent back to the model, not typed)
+    pub fn is_tool_result_only(&self) -> bool {
+        match self {
+            MessageContent::Text(_) => false,
+            MessageContent::Structured(blocks) => {
+                !blocks.is_empty()
+                    && blocks
+                        .iter()
+                        .all(|b| matches!(b, ContentBlock::ToolResult { .. }))
+            }
+        }
+    }
+
     /// Check if content starts with system tags (not real user input)
     pub fn is_system_content(&self) -> bool {
         let text = self.as_text();
@@ -222,41 +447,127 @@ pub enum ContentBlock {
     ToolResult { content: serde_json::Value },
     ToolUse { name: String, input: Option<serde_json::Value> },
     Thinking { thinking: String },
+    Image {
+        #[serde(default)]
+        source: MediaSource,
+    },
+    Document {
+        #[serde(default)]
+        source: MediaSource,
+        title: Option<String>,
+    },
     #[serde(other)]
     Other,
 }
 
+/// Source of an image or document block; base64 payloads are kept only as a length
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct MediaSource {
+    pub media_type: Option<String>,
+    #[serde(default, rename = "data", deserialize_with = "deserialize_data_len")]
+    pub data_len: usize,
+}
+
+impl MediaSource {
+    /// Short format name, e.g. "png" for "image/png"
+    fn format(&self) -> Option<&str> {
+        self.media_type
+            .as_deref()
+            .map(|m| m.rsplit('/').next().unwrap_or(m))
+    }
+
+    /// Decoded size of a base64 payload (0 for URL or file sources)
+    fn size_bytes(&self) -> u64 {
+        (self.data_len as u64 * 3) / 4
+    }
+}
+
+fn deserialize_data_len<'de, D>(deserializer: D) -> Result<usize, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let data = Option::<String>::deserialize(deserializer)?;
+    Ok(data.map(|d| d.len()).unwrap_or(0))
+}
+
 impl ContentBlock {
-    pub fn as_text(&self) -> Option<String> {
+    /// Whether this is an image or document block
+    pub fn is_attachment(&self) -> bool {
+        matches!(self, ContentBlock::Image { .. } | ContentBlock::Document { .. })
+    }
+
+    /// Render a block with the given options
+    pub fn render(&self, options: &RenderOptions) -> Option<String> {
         match self {
-            ContentBlock::Text { text } => Some(text.clone()),
-            ContentBlock::Thinking { thinking } => Some(format!("💭 {}", thinking)),
+            ContentBlock::Text { text } => Some(options.clean(text)),
+            ContentBlock::Thinking { .. } if !options.thinking => None,
+            ContentBlock::ToolUse { .. } | ContentBlock::ToolResult { .. } if !options.tools => None,
+            ContentBlock::Thinking { thinking } if options.plain => {
+                Some(format!("[thinking] {}", thinking))
+            }
+            ContentBlock::Thinking { thinking } => {
+                Some(format!("{} {}", options.symbols().thinking, thinking))
+            }
             ContentBlock::ToolUse { name, input } => {
                 let input_preview = input
                     .as_ref()
                     .and_then(|v| v.get("command").or(v.get("pattern")).or(v.get("file_path")))
                     .and_then(|v| v.as_str())
-                    .map(|s| {
-                        let char_count = s.chars().count();
-                        if char_count > 60 {
-                            let truncated: String = s.chars().take(57).collect();
-                            format!(" \"{}...\"", truncated)
-                        } else {
-                            format!(" \"{}\"", s)
-                        }
-                    })
+                    .map(|s| format!(" \"{}\"", options.tool_text(s, TOOL_INPUT_CHARS)))
                     .unwrap_or_default();
-                Some(format!("🔧 {}{}", name, input_preview))
+                if options.plain {
+                    Some(format!("[tool: {}{}]", name, input_preview))
+                } else {
+                    Some(format!("{} {}{}", options.symbols().tool, name, input_preview))
+                }
             }
             ContentBlock::ToolResult { content } => {
-                let result_text = Self::format_tool_result(content);
-                Some(format!("📋 {}", result_text))
+                let result_text = Self::format_tool_result(content, options);
+                if options.plain {
+                    Some(format!("[result] {}", result_text))
+                } else {
+                    Some(format!("{} {}", options.symbols().result, result_text))
+                }
+            }
+            ContentBlock::Image { source } => {
+                Some(Self::format_attachment("🖼", "image", None, source, options))
             }
+            ContentBlock::Document { source, title } => Some(Self::format_attachment(
+                "📄",
+                "document",
+                title.as_deref(),
+                source,
+                options,
+            )),
             ContentBlock::Other => None,
         }
     }
 
-    fn format_tool_result(content: &serde_json::Value) -> String {
+    /// Placeholder for non-text content, e.g. `🖼 image (png, 42 KiB)`
+    fn format_attachment(
+        icon: &str,
+        kind: &str,
+        title: Option<&str>,
+        source: &MediaSource,
+        options: &RenderOptions,
+    ) -> String {
+        let size = source.size_bytes();
+        let details: Vec<String> = title
+            .map(|t| format!("\"{}\"", t))
+            .into_iter()
+            .chain(source.format().map(str::to_string))
+            .chain((size > 0).then(|| humansize::format_size(size, humansize::BINARY)))
+            .collect();
+        // ASCII mode uses the bracketed plain form; there's no short ASCII icon
+        match (options.plain || options.ascii, details.is_empty()) {
+            (true, true) => format!("[{}]", kind),
+            (true, false) => format!("[{}: {}]", kind, details.join(", ")),
+            (false, true) => format!("{} {}", icon, kind),
+            (false, false) => format!("{} {} ({})", icon, kind, details.join(", ")),
+        }
+    }
+
+    fn format_tool_result(content: &serde_json::Value, options: &RenderOptions) -> String {
         // Handle array of content blocks (common format)
         if let Some(arr) = content.as_array() {
             let texts: Vec<&str> = arr
@@ -264,14 +575,14 @@ impl ContentBlock {
                 .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
                 .collect();
             if !texts.is_empty() {
-                let combined = texts.join("\n");
-                return Self::truncate_result(&combined, 200);
+                let combined = options.clean(&texts.join("\n"));
+                return options.tool_text(&combined, TOOL_RESULT_CHARS);
             }
         }
 
         // Handle direct string
         if let Some(s) = content.as_str() {
-            return Self::truncate_result(s, 200);
+            return options.tool_text(&options.clean(s), TOOL_RESULT_CHARS);
         }
 
         // Fallback
@@ -291,16 +602,22 @@ impl ContentBlock {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct AssistantMessage {
-    pub role: String,
+    #[serde(rename = "role")]
+    pub _role: String,
     pub content: Vec<ContentBlock>,
     pub model: Option<String>,
 }
 
 impl AssistantMessage {
     pub fn as_text(&self) -> String {
+        self.render(&RenderOptions::default())
+    }
+
+    /// Render the message with the given options
+    pub fn render(&self, options: &RenderOptions) -> String {
         self.content
             .iter()
-            .filter_map(|b| b.as_text())
+            .filter_map(|b| b.render(options))
             .collect::<Vec<_>>()
             .join("\n")
     }
@@ -314,7 +631,7 @@ pub struct DisplayMessage {
     pub content: String,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MessageRole {
     User,
     Assistant,
@@ -370,6 +687,146 @@ mod tests {
         assert!(!content.is_system_content());
     }
 
+    #[test]
+    fn test_render_plain_tool_use() {
+        let block = ContentBlock::ToolUse {
+            name: "Bash".to_string(),
+            input: Some(serde_json::json!({"command": "ls -la"})),
+        };
+        assert_eq!(
+            block.render(&RenderOptions::default()).as_deref(),
+            Some("🔧 Bash \"ls -la\"")
+        );
+        let plain = RenderOptions {
+            plain: true,
+            ..Default::default()
+        };
+        assert_eq!(block.render(&plain).as_deref(), Some("[tool: Bash \"ls -la\"]"));
+    }
+
+    #[test]
+    fn test_render_ascii_markers() {
+        let ascii = RenderOptions {
+            ascii: true,
+            ..Default::default()
+        };
+        let call = ContentBlock::ToolUse {
+            name: "Bash".to_string(),
+            input: Some(serde_json::json!({"command": "ls"})),
+        };
+        let thinking = ContentBlock::Thinking {
+            thinking: "hmm".to_string(),
+        };
+        let result = ContentBlock::ToolResult {
+            content: serde_json::json!("ok"),
+        };
+        assert_eq!(call.render(&ascii).as_deref(), Some("[tool] Bash \"ls\""));
+        assert_eq!(thinking.render(&ascii).as_deref(), Some("[think] hmm"));
+        assert_eq!(result.render(&ascii).as_deref(), Some("[result] ok"));
+    }
+
+    #[test]
+    fn test_render_strips_ansi_from_tool_results() {
+        let block = ContentBlock::ToolResult {
+            content: serde_json::json!("\u{1b}[31mFAILED\u{1b}[0m 2 tests"),
+        };
+        assert_eq!(
+            block.render(&RenderOptions::default()).as_deref(),
+            Some("📋 FAILED 2 tests")
+        );
+        let raw = RenderOptions {
+            strip_ansi: false,
+            ..Default::default()
+        };
+        assert_eq!(
+            block.render(&raw).as_deref(),
+            Some("📋 \u{1b}[31mFAILED\u{1b}[0m 2 tests")
+        );
+    }
+
+    #[test]
+    fn test_render_full_tools_skips_truncation() {
+        let long = "x".repeat(250);
+        let result = ContentBlock::ToolResult {
+            content: serde_json::json!(long),
+        };
+        let call = ContentBlock::ToolUse {
+            name: "Bash".to_string(),
+            input: Some(serde_json::json!({ "command": long })),
+        };
+        let short = RenderOptions::default();
+        let full = RenderOptions {
+            full_tools: true,
+            ..Default::default()
+        };
+
+        assert_eq!(result.render(&short).unwrap().chars().count(), "📋 ".chars().count() + 200);
+        assert_eq!(result.render(&full).unwrap(), format!("📋 {}", long));
+        assert!(call.render(&short).unwrap().ends_with("...\""));
+        assert_eq!(call.render(&full).unwrap(), format!("🔧 Bash \"{}\"", long));
+    }
+
+    #[test]
+    fn test_render_strips_thinking_and_tools() {
+        let content = MessageContent::Structured(vec![
+            ContentBlock::Thinking {
+                thinking: "hmm".to_string(),
+            },
+            ContentBlock::Text {
+                text: "answer".to_string(),
+            },
+            ContentBlock::ToolUse {
+                name: "Read".to_string(),
+                input: None,
+            },
+        ]);
+        let options = RenderOptions {
+            thinking: false,
+            tools: false,
+            ..Default::default()
+        };
+        assert_eq!(content.render(&options), "answer");
+        assert_eq!(content.as_text(), "💭 hmm\nanswer\n🔧 Read");
+    }
+
+    #[test]
+    fn test_render_image_and_document_placeholders() {
+        let content: MessageContent = serde_json::from_value(serde_json::json!([
+            {"type": "image", "source": {"type": "base64", "media_type": "image/png", "data": "A".repeat(4096)}},
+            {"type": "document", "title": "spec", "source": {"type": "url", "url": "https://example.com/a.pdf"}},
+            {"type": "text", "text": "what is this?"}
+        ]))
+        .unwrap();
+        assert_eq!(content.attachment_count(), 2);
+        assert_eq!(
+            content.as_text(),
+            "🖼 image (png, 3 KiB)\n📄 document (\"spec\")\nwhat is this?"
+        );
+        let plain = RenderOptions {
+            plain: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            content.render(&plain),
+            "[image: png, 3 KiB]\n[document: \"spec\"]\nwhat is this?"
+        );
+    }
+
+    #[test]
+    fn test_sidecar_dir_and_id_for_compressed_sessions() {
+        assert_eq!(session_id_from_file_name("abc.jsonl"), Some("abc"));
+        assert_eq!(session_id_from_file_name("abc.jsonl.gz"), Some("abc"));
+        assert_eq!(session_id_from_file_name("abc.json"), None);
+        assert_eq!(session_id_from_file_name(".jsonl"), None);
+
+        let session = Session {
+            path: PathBuf::from("/tmp/proj/abc.jsonl.gz"),
+            ..Session::test("abc")
+        };
+        assert!(session.is_compressed());
+        assert_eq!(session.sidecar_dir(), PathBuf::from("/tmp/proj/abc"));
+    }
+
     #[test]
     fn test_is_system_content_case_insensitive() {
         let content = MessageContent::Text("<SYSTEM-REMINDER>test</SYSTEM-REMINDER>".to_string());
@@ -1,44 +1,96 @@
 use anyhow::Result;
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseEventKind};
+use chrono::{DateTime, Utc};
+use crossterm::event::{
+    self, Event, KeyCode, KeyEventKind, KeyModifiers, MouseButton, MouseEventKind,
+};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     prelude::*,
-    style::{Color, Modifier, Style},
+    style::{Modifier, Style},
     text::{Line, Span},
     widgets::{
-        Block, Borders, Cell, Clear, List, ListItem, Paragraph, Row, Table, TableState, Wrap,
+        Block, Borders, Cell, Clear, Gauge, List, ListItem, Paragraph, Row, Table, TableState, Wrap,
     },
     Frame,
 };
-use std::io::Write;
+use crossterm::{
+    event::{DisableMouseCapture, EnableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use std::io::{self, Write};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, TryRecvError};
+use std::time::{Duration, Instant};
 
-use super::highlight::{parse_code_blocks, CodeBlockInfo, Highlighter};
-use super::state::{DialogAction, UiState, View};
+use super::highlight::{markdown_styles, parse_code_blocks, CodeBlockInfo, Highlighter};
+use super::state::{ActionRecord, Column, DialogAction, MergeResult, UiState, View};
+use super::theme::Theme;
 use crate::actions;
-use crate::session::{get_session_preview, load_session_messages, load_session_metadata};
+use crate::session::{
+    diff_user_messages, get_session_preview, load_session_messages, load_session_metadata,
+    save_bookmarks, shared_prefix_len, DiffOp, DisplayMessage, SearchIndex, Session,
+};
 
-use crate::utils::format_tokens;
+use crate::utils::{
+    find_in_path, format_age, format_duration, format_relative_time, format_time, format_tokens,
+    Symbols,
+};
 
-fn copy_to_clipboard(text: &str) -> bool {
-    // Platform-specific clipboard commands
-    #[cfg(target_os = "macos")]
-    let commands: &[(&str, &[&str])] = &[("pbcopy", &[])];
+/// No clipboard command accepted the text
+#[derive(Debug, thiserror::Error)]
+#[error("No clipboard tool found (tried: {})", tried.join(", "))]
+struct ClipboardError {
+    tried: Vec<&'static str>,
+}
 
-    #[cfg(target_os = "linux")]
-    let commands: &[(&str, &[&str])] = &[
-        ("xclip", &["-selection", "clipboard"]),
-        ("xsel", &["--clipboard", "--input"]),
-        ("wl-copy", &[]),
-    ];
+/// Clipboard commands to try, ordered by what is most likely to work on this platform
+fn clipboard_commands() -> Vec<(&'static str, &'static [&'static str])> {
+    let mut commands: Vec<(&'static str, &'static [&'static str])> = if cfg!(target_os = "macos") {
+        vec![("pbcopy", &[])]
+    } else if cfg!(target_os = "windows") {
+        vec![
+            ("clip.exe", &[]),
+            ("powershell.exe", &["-NoProfile", "-Command", "Set-Clipboard -Value $input"]),
+        ]
+    } else if std::env::var_os("WAYLAND_DISPLAY").is_some() {
+        vec![
+            ("wl-copy", &[]),
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+        ]
+    } else {
+        vec![
+            ("xclip", &["-selection", "clipboard"]),
+            ("xsel", &["--clipboard", "--input"]),
+            ("wl-copy", &[]),
+        ]
+    };
+
+    // Remaining tools as fallbacks (e.g. clip.exe under WSL)
+    for fallback in [("pbcopy", &[] as &[&str]), ("clip.exe", &[])] {
+        if !commands.iter().any(|(cmd, _)| *cmd == fallback.0) {
+            commands.push(fallback);
+        }
+    }
 
-    #[cfg(target_os = "windows")]
-    let commands: &[(&str, &[&str])] = &[("clip", &[])];
+    commands
+}
+
+/// First clipboard tool installed on `$PATH` (what `y` will try first), plus all candidates
+pub fn detect_clipboard_tool() -> (Option<&'static str>, Vec<&'static str>) {
+    let candidates: Vec<&'static str> = clipboard_commands().into_iter().map(|(cmd, _)| cmd).collect();
+    let found = candidates.iter().copied().find(|cmd| find_in_path(cmd).is_some());
+    (found, candidates)
+}
 
-    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
-    let commands: &[(&str, &[&str])] = &[];
+/// Copy text to the clipboard, returning the name of the tool that succeeded
+fn copy_to_clipboard(text: &str) -> Result<&'static str, ClipboardError> {
+    let commands = clipboard_commands();
 
-    for (cmd, args) in commands {
+    for (cmd, args) in &commands {
         if let Ok(mut child) = Command::new(cmd)
             .args(*args)
             .stdin(Stdio::piped())
@@ -50,35 +102,208 @@ fn copy_to_clipboard(text: &str) -> bool {
                 if stdin.write_all(text.as_bytes()).is_ok() {
                     drop(stdin); // Close stdin so clipboard tool knows we're done
                                  // Wait for child with a short timeout to confirm success
-                    return child.wait().map(|status| status.success()).unwrap_or(false);
+                    if child.wait().map(|status| status.success()).unwrap_or(false) {
+                        return Ok(cmd);
+                    }
                 }
             }
         }
     }
-    false
+
+    Err(ClipboardError {
+        tried: commands.into_iter().map(|(cmd, _)| cmd).collect(),
+    })
+}
+
+/// Shell command that resumes a session in its working directory
+fn resume_command(session: &Session) -> String {
+    format!("cd {} && claude --resume {}", session.project_path, session.id)
+}
+
+/// Command for opening a directory: `$VISUAL`/`$EDITOR` (runs in the terminal) or the
+/// platform file manager (runs detached)
+pub fn open_dir_command() -> (Vec<String>, bool) {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty());
+    if let Some(editor) = editor {
+        return (editor.split_whitespace().map(String::from).collect(), true);
+    }
+
+    (vec![file_manager_command().to_string()], false)
+}
+
+/// Platform command that opens a directory in the file manager
+fn file_manager_command() -> &'static str {
+    if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(target_os = "windows") {
+        "explorer"
+    } else {
+        "xdg-open"
+    }
+}
+
+/// Command for viewing a raw session file: `$PAGER`, then `$VISUAL`/`$EDITOR`, then `less`
+pub fn open_file_command() -> Vec<String> {
+    let viewer = ["PAGER", "VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|var| std::env::var(var).ok())
+        .find(|v| !v.trim().is_empty());
+    match viewer {
+        Some(viewer) => viewer.split_whitespace().map(String::from).collect(),
+        None if cfg!(target_os = "windows") => vec!["more".to_string()],
+        None => vec!["less".to_string()],
+    }
+}
+
+/// How often `--watch` rescans the projects directory
+const WATCH_INTERVAL: Duration = Duration::from_secs(2);
+/// Apply a change even if files are still being written after this long
+const WATCH_MAX_DELAY: Duration = Duration::from_secs(10);
+
+/// Smallest terminal the layout is drawn in; anything smaller shows a notice
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 8;
+
+/// Help overlay lines for the preview (the list's are built in `draw_help_overlay`)
+const PREVIEW_HELP: &[&str] = &[
+    "",
+    "  Navigation",
+    "  j/k, Up/Down    Move cursor",
+    "  g/G, Home/End   Go to top/bottom",
+    "  PgUp/PgDn       Page up/down",
+    "  [ / ]           Previous/next message",
+    "  h/l, Left/Right Scroll sideways (wrap off)",
+    "",
+    "  Search",
+    "  /               Search in session",
+    "  n / N           Next/previous match",
+    "  Enter           Keep search, back to reading",
+    "  Esc             Clear search",
+    "",
+    "  Copy",
+    "  Space           Select message",
+    "  A               Clear message selection",
+    "  c               Copy selected/current message",
+    "  y               Copy message under cursor",
+    "  C               Copy entire session",
+    "  e               Copy session as Markdown",
+    "",
+    "  Display",
+    "  w               Toggle line wrap",
+    "  t               Show/hide thinking",
+    "  T               Show/hide tool calls",
+    "  E               Expand/truncate tool inputs and results",
+    "  x               Strip/keep ANSI escape codes",
+    "  m               Toggle Markdown styling",
+    "",
+    "  ?, F1           Toggle help",
+    "  q, Esc          Back to list",
+    "",
+];
+
+/// Help overlay lines for the search prompt (`?` is typed, so only F1 opens it)
+const SEARCH_HELP: &[&str] = &[
+    "",
+    "  Type            Filter sessions as you type",
+    "  Backspace       Delete last character",
+    "  Tab             Toggle whole-word matching",
+    "  Shift+Tab       Toggle fuzzy matching (titles only, best first)",
+    "  Enter           Keep filter, back to list",
+    "  Esc             Clear filter, back to list",
+    "",
+    "  In the list, Enter opens a session",
+    "  at its first match (n/N for more)",
+    "",
+    "  F1              Toggle help",
+    "",
+];
+
+/// Help overlay lines for the session comparison
+const DIFF_HELP: &[&str] = &[
+    "",
+    "  j/k, Up/Down    Scroll",
+    "  g/G, Home/End   Go to top/bottom",
+    "  PgUp/PgDn       Page up/down",
+    "",
+    "  -               Only in the older session",
+    "  +               Only in the newer session",
+    "",
+    "  ?, F1           Toggle help",
+    "  q, Esc          Back to list",
+    "",
+];
+
+/// (path, modified, size) of every session, used to detect changes on disk
+type Fingerprint = Vec<(PathBuf, DateTime<Utc>, u64)>;
+
+fn fingerprint(sessions: &[Session]) -> Fingerprint {
+    let mut items: Fingerprint = sessions
+        .iter()
+        .map(|s| (s.path.clone(), s.modified, s.size_bytes))
+        .collect();
+    items.sort();
+    items
+}
+
+/// Preview loads that finish within this long open without showing the spinner
+const PREVIEW_SPINNER_DELAY: Duration = Duration::from_millis(150);
+
+/// Messages for the preview, parsed on a background thread so Esc can abandon a huge file
+struct PreviewLoad {
+    rx: Receiver<Result<Vec<DisplayMessage>>>,
+    started: Instant,
 }
 
 pub struct App {
     pub state: UiState,
     pub should_quit: bool,
+    /// Session directory and id to run `claude --resume` in after the TUI exits
+    pub resume_on_exit: Option<(PathBuf, String)>,
     needs_refresh: bool,
+    /// Drop the search index and reparse every session on the next refresh
+    rebuild_cache: bool,
+    last_watch_poll: Instant,
+    /// Changed snapshot waiting to settle before it is applied, and when it was first seen
+    pending_watch: Option<(Fingerprint, Instant)>,
+    /// Directory to open once the event handler returns (needs the terminal)
+    pending_open: Option<PathBuf>,
+    /// Session file to open in a pager after the current frame
+    pending_raw: Option<PathBuf>,
+    /// Preview still being parsed (the list stays up with a spinner)
+    preview_load: Option<PreviewLoad>,
     table_state: TableState,
     highlighter: Highlighter,
     code_blocks: Vec<CodeBlockInfo>,
+    /// Content rows visible in the preview pane (for paging)
+    preview_height: usize,
+    /// Where the session table was last drawn (for mouse clicks)
+    table_area: Rect,
 }
 
 impl App {
-    pub fn new(state: UiState) -> Self {
+    pub fn new(state: UiState, highlighter: Highlighter) -> Self {
         let mut table_state = TableState::default();
         table_state.select(Some(0));
 
         Self {
             state,
             should_quit: false,
+            resume_on_exit: None,
             needs_refresh: false,
+            rebuild_cache: false,
+            last_watch_poll: Instant::now(),
+            pending_watch: None,
+            pending_open: None,
+            pending_raw: None,
+            preview_load: None,
             table_state,
-            highlighter: Highlighter::new(),
+            highlighter,
             code_blocks: Vec::new(),
+            preview_height: 20,
+            table_area: Rect::default(),
         }
     }
 
@@ -86,15 +311,49 @@ impl App {
         // Load all metadata upfront for accurate display
         self.load_all_metadata(terminal)?;
 
+        if self.state.preview_only {
+            self.open_preview();
+            // Nothing to go back to, so just wait for the load
+            if let Some(load) = self.preview_load.take() {
+                let result = load
+                    .rx
+                    .recv()
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!("loader stopped")));
+                self.finish_preview_load(result);
+            }
+            if self.state.view != View::Preview {
+                let reason = self.state.status_message.take();
+                anyhow::bail!(reason.unwrap_or_else(|| "Session not found".to_string()));
+            }
+        }
+
         while !self.should_quit {
             terminal.draw(|f| self.draw(f))?;
             self.handle_events()?;
+            self.poll_preview_load();
 
             // Handle refresh with terminal access for progress display
             if self.needs_refresh {
                 self.needs_refresh = false;
                 self.do_refresh(terminal)?;
             }
+
+            if let Some(dir) = self.pending_open.take() {
+                self.open_directory(terminal, &dir)?;
+            }
+            if let Some(path) = self.pending_raw.take() {
+                self.open_raw_file(terminal, &path)?;
+            }
+
+            // Don't reshuffle sessions under an open preview or dialog
+            let idle_view = match self.state.view {
+                View::List | View::Search => self.preview_load.is_none(),
+                View::Help => matches!(self.state.help_context, View::List | View::Search),
+                _ => false,
+            };
+            if self.state.watch && idle_view && self.last_watch_poll.elapsed() >= WATCH_INTERVAL {
+                self.poll_watch();
+            }
         }
 
         Ok(())
@@ -102,25 +361,26 @@ impl App {
 
     fn load_all_metadata(&mut self, terminal: &mut ratatui::Terminal<impl Backend>) -> Result<()> {
         let total = self.state.sessions.len();
+        let chars_per_token = self.state.chars_per_token;
+        let theme = self.state.color_theme.theme();
 
         for (i, session) in self.state.sessions.iter_mut().enumerate() {
             if session.first_message.is_none() {
-                let _ = load_session_metadata(session);
+                let _ = load_session_metadata(session, chars_per_token);
             }
 
             // Update progress every 50 sessions
             if i % 50 == 0 || i == total - 1 {
-                terminal.draw(|f| {
-                    let area = f.size();
-                    let msg = format!("Loading session metadata... {}/{}", i + 1, total);
-                    let paragraph = ratatui::widgets::Paragraph::new(msg)
-                        .style(ratatui::style::Style::default().fg(ratatui::style::Color::Cyan))
-                        .alignment(ratatui::layout::Alignment::Center);
-                    f.render_widget(paragraph, area);
-                })?;
+                let label = "Loading session metadata";
+                terminal.draw(|f| draw_progress(f, theme, label, i + 1, total))?;
             }
         }
 
+        // Filters (system-only, code, search text) and name sorting need the metadata above
+        self.state.apply_filters();
+        self.table_state.select(Some(self.state.cursor));
+        self.state.update_search_index();
+
         Ok(())
     }
 
@@ -135,16 +395,38 @@ impl App {
                     // Clear status message on any key
                     self.state.clear_status();
 
+                    // While a preview loads, only Esc (cancel) does anything
+                    if self.preview_load.is_some() {
+                        if key.code == KeyCode::Esc {
+                            self.preview_load = None;
+                            self.state.set_status("Preview canceled".to_string());
+                        }
+                        return Ok(());
+                    }
+
+                    // F1 works everywhere, including views where `?` is typed text
+                    let has_help = matches!(
+                        self.state.view,
+                        View::List | View::Preview | View::Search | View::Diff
+                    );
+                    if key.code == KeyCode::F(1) && has_help {
+                        self.state.open_help();
+                        return Ok(());
+                    }
+
                     match self.state.view {
                         View::List => self.handle_list_keys(key.code, key.modifiers),
                         View::Preview => self.handle_preview_keys(key.code),
                         View::Search => self.handle_search_keys(key.code),
                         View::Help => self.handle_help_keys(key.code),
                         View::Confirm => self.handle_confirm_keys(key.code),
+                        View::ProjectPicker => self.handle_picker_keys(key.code),
+                        View::ArchiveName => self.handle_archive_name_keys(key.code),
+                        View::Diff => self.handle_diff_keys(key.code),
                     }
                 }
-                Event::Mouse(mouse) => {
-                    self.handle_mouse(mouse.kind);
+                Event::Mouse(mouse) if self.preview_load.is_none() => {
+                    self.handle_mouse(mouse.kind, mouse.column, mouse.row);
                 }
                 _ => {}
             }
@@ -152,8 +434,27 @@ impl App {
         Ok(())
     }
 
-    fn handle_mouse(&mut self, kind: MouseEventKind) {
+    fn handle_mouse(&mut self, kind: MouseEventKind, column: u16, row: u16) {
         match kind {
+            MouseEventKind::Down(MouseButton::Left) if self.state.view == View::List => {
+                let area = self.table_area;
+                let in_table = column >= area.x
+                    && column < area.x + area.width
+                    && row > area.y // first row is the header
+                    && row < area.y + area.height;
+                if !in_table {
+                    return;
+                }
+
+                let pos = self.state.scroll_offset + (row - area.y - 1) as usize;
+                if pos == self.state.cursor {
+                    // Second click on the highlighted row (or a double-click) opens it
+                    self.open_preview();
+                } else if pos < self.state.filtered_indices.len() {
+                    self.state.move_cursor_to(pos);
+                    self.table_state.select(Some(self.state.cursor));
+                }
+            }
             MouseEventKind::ScrollUp => match self.state.view {
                 View::List => {
                     self.state.cursor_up();
@@ -161,6 +462,8 @@ impl App {
                 }
                 View::Preview => {
                     self.state.preview_scroll = self.state.preview_scroll.saturating_sub(3);
+                    let cursor = self.state.preview_cursor.saturating_sub(3);
+                    self.state.set_preview_cursor(cursor);
                 }
                 _ => {}
             },
@@ -169,10 +472,12 @@ impl App {
                     self.state.cursor_down();
                     self.table_state.select(Some(self.state.cursor));
                 }
-                View::Preview => {
-                    if self.state.preview_scroll + 3 < self.state.preview_lines.len() {
-                        self.state.preview_scroll += 3;
-                    }
+                View::Preview
+                    if self.state.preview_scroll + 3 < self.state.preview_lines.len() =>
+                {
+                    self.state.preview_scroll += 3;
+                    let cursor = self.state.preview_cursor + 3;
+                    self.state.set_preview_cursor(cursor);
                 }
                 _ => {}
             },
@@ -206,11 +511,11 @@ impl App {
                     .select(Some(self.state.filtered_indices.len().saturating_sub(1)));
             }
             KeyCode::PageUp => {
-                self.state.page_up(20);
+                self.state.page_up(self.state.visible_height);
                 self.table_state.select(Some(self.state.cursor));
             }
             KeyCode::PageDown => {
-                self.state.page_down(20);
+                self.state.page_down(self.state.visible_height);
                 self.table_state.select(Some(self.state.cursor));
             }
             KeyCode::Enter => {
@@ -230,15 +535,29 @@ impl App {
             KeyCode::Char('A') => {
                 self.state.clear_selection();
             }
+            KeyCode::Char('I') => {
+                self.state.invert_selection();
+            }
+            KeyCode::Char('E') => {
+                let count = self.state.select_empty();
+                self.state
+                    .set_status(format!("Selected {} empty session(s)", count));
+            }
             KeyCode::Char('/') => {
                 self.state.view = View::Search;
             }
             KeyCode::Char('?') => {
-                self.state.view = View::Help;
+                self.state.open_help();
             }
             KeyCode::Char('p') => {
                 self.state.cycle_project_filter();
             }
+            KeyCode::Char('P') => {
+                self.state.open_project_picker();
+            }
+            KeyCode::Char('C') => {
+                self.open_diff();
+            }
             KeyCode::Char('d') => {
                 self.confirm_delete();
             }
@@ -246,10 +565,27 @@ impl App {
                 self.confirm_delete_older();
             }
             KeyCode::Char('e') => {
-                self.do_export();
+                self.confirm_export();
+            }
+            KeyCode::Char('J') => {
+                self.confirm_merge_export();
             }
             KeyCode::Char('z') => {
-                self.do_archive();
+                self.confirm_archive();
+            }
+            KeyCode::Char('u') => {
+                self.confirm_undo();
+            }
+            KeyCode::Char('Z') => {
+                if self.state.action_targets().is_empty() {
+                    self.state.set_status("No sessions to archive".to_string());
+                } else {
+                    self.state.open_archive_name_prompt();
+                }
+            }
+            KeyCode::Char('r') if modifiers.contains(KeyModifiers::CONTROL) => {
+                self.rebuild_cache = true;
+                self.needs_refresh = true;
             }
             KeyCode::Char('r') => {
                 self.needs_refresh = true;
@@ -262,28 +598,112 @@ impl App {
                 self.state.toggle_sort_direction();
                 self.table_state.select(Some(self.state.cursor));
             }
-            KeyCode::Char('y') => {
-                if let Some(session) = self.state.get_current_session() {
-                    let cmd = format!(
-                        "cd {} && claude --resume {}",
-                        session.project_path, session.id
-                    );
-                    if copy_to_clipboard(&cmd) {
-                        self.state.set_status(format!("Copied: {}", cmd));
+            KeyCode::Char('O') => {
+                if let Some(session) = self.state.current_session() {
+                    let dir = PathBuf::from(&session.project_path);
+                    if dir.is_dir() {
+                        self.pending_open = Some(dir);
                     } else {
                         self.state
-                            .set_status("Failed to copy (clipboard tool not found?)".to_string());
+                            .set_status(format!("Directory no longer exists: {}", dir.display()));
+                    }
+                }
+            }
+            KeyCode::Char('L') => self.open_last_output_dir(),
+            KeyCode::Char('S') => self.state.cycle_size_filter(),
+            KeyCode::Char('F') => self.state.toggle_float_active(),
+            KeyCode::Char('t') => {
+                self.state.toggle_relative_time();
+            }
+            KeyCode::Char('U') => self.state.toggle_local_time(),
+            KeyCode::Char('#') => {
+                self.state.toggle_show_ids();
+            }
+            KeyCode::Char('w') => {
+                self.state.cycle_date_range();
+            }
+            KeyCode::Char('i') => {
+                self.state.toggle_agents();
+            }
+            KeyCode::Char('T') => self.state.cycle_color_theme(),
+            KeyCode::Char('H') => self.state.toggle_system_only(),
+            KeyCode::Char('b') => {
+                self.state.toggle_code_only();
+            }
+            KeyCode::Char('m') => {
+                if let Some(pinned) = self.state.toggle_pin() {
+                    self.table_state.select(Some(self.state.cursor));
+                    match save_bookmarks(&self.state.pinned) {
+                        Ok(()) => self
+                            .state
+                            .set_status(if pinned { "Pinned" } else { "Unpinned" }.to_string()),
+                        Err(e) => self.state.set_status(format!("Failed to save pins: {}", e)),
+                    }
+                }
+            }
+            KeyCode::Char('M') => {
+                self.state.toggle_pinned_only();
+                self.table_state.select(Some(self.state.cursor));
+            }
+            KeyCode::Char('f') => {
+                self.state.toggle_active_projects();
+                self.table_state.select(Some(self.state.cursor));
+            }
+            KeyCode::Char('R') => {
+                self.resume_session();
+            }
+            KeyCode::Char('X') => {
+                if let Some(session) = self.state.current_session() {
+                    self.pending_raw = Some(session.path.clone());
+                }
+            }
+            KeyCode::Char('y') if modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(session) = self.state.get_current_session() {
+                    let id = session.id.clone();
+                    match copy_to_clipboard(&id) {
+                        Ok(backend) => {
+                            self.state
+                                .set_status(format!("Copied id via {}: {}", backend, id));
+                        }
+                        Err(e) => self.state.set_status(e.to_string()),
+                    }
+                }
+            }
+            KeyCode::Char('y') => {
+                if let Some(session) = self.state.get_current_session() {
+                    let cmd = resume_command(session);
+                    match copy_to_clipboard(&cmd) {
+                        Ok(backend) => {
+                            self.state
+                                .set_status(format!("Copied via {}: {}", backend, cmd));
+                        }
+                        Err(e) => self.state.set_status(e.to_string()),
+                    }
+                }
+            }
+            KeyCode::Char('B') => {
+                let targets = self.state.action_targets();
+                if !targets.is_empty() {
+                    let count = targets.len();
+                    let cmds: Vec<String> = targets.into_iter().map(resume_command).collect();
+                    match copy_to_clipboard(&(cmds.join("\n") + "\n")) {
+                        Ok(backend) => self.state.set_status(format!(
+                            "Copied {} resume command(s) via {}",
+                            count, backend
+                        )),
+                        Err(e) => self.state.set_status(e.to_string()),
                     }
                 }
             }
             KeyCode::Char('Y') => {
                 if let Some(session) = self.state.get_current_session() {
                     let path = session.path.display().to_string();
-                    if copy_to_clipboard(&path) {
-                        self.state.set_status(format!("Copied path: {}", path));
-                    } else {
-                        self.state
-                            .set_status("Failed to copy (xclip not found?)".to_string());
+                    match copy_to_clipboard(&path) {
+                        Ok(backend) => {
+                            self.state
+                                .set_status(format!("Copied path via {}: {}", backend, path));
+                        }
+                        Err(e) => self.state.set_status(e.to_string()),
                     }
                 }
             }
@@ -315,36 +735,45 @@ impl App {
         }
 
         match code {
+            KeyCode::Char('q') | KeyCode::Esc if self.state.preview_only => {
+                self.should_quit = true;
+            }
             KeyCode::Char('q') | KeyCode::Esc => {
                 self.state.clear_preview_search();
                 self.state.clear_section_selection();
                 self.state.view = View::List;
                 self.state.preview_lines.clear();
             }
+            // The renderer scrolls to keep the cursor on screen
             KeyCode::Char('j') | KeyCode::Down => {
-                if self.state.preview_scroll + 1 < self.state.preview_lines.len() {
-                    self.state.preview_scroll += 1;
-                }
+                let cursor = self.state.preview_cursor + 1;
+                self.state.set_preview_cursor(cursor);
             }
             KeyCode::Char('k') | KeyCode::Up => {
-                self.state.preview_scroll = self.state.preview_scroll.saturating_sub(1);
+                let cursor = self.state.preview_cursor.saturating_sub(1);
+                self.state.set_preview_cursor(cursor);
             }
             KeyCode::PageDown => {
-                self.state.preview_scroll = (self.state.preview_scroll + 20)
+                let scroll = (self.state.preview_scroll + self.preview_height)
                     .min(self.state.preview_lines.len().saturating_sub(1));
+                self.state.jump_preview_to(scroll);
             }
             KeyCode::PageUp => {
-                self.state.preview_scroll = self.state.preview_scroll.saturating_sub(20);
+                let scroll = self.state.preview_scroll.saturating_sub(self.preview_height);
+                self.state.jump_preview_to(scroll);
             }
             KeyCode::Char('g') | KeyCode::Home => {
-                self.state.preview_scroll = 0;
+                self.state.jump_preview_to(0);
             }
             KeyCode::Char('G') | KeyCode::End => {
-                self.state.preview_scroll = self.state.preview_lines.len().saturating_sub(1);
+                self.state.set_preview_cursor(usize::MAX);
             }
             KeyCode::Char('/') => {
                 self.state.preview_search_active = true;
             }
+            KeyCode::Char('?') => {
+                self.state.open_help();
+            }
             KeyCode::Char('n') => {
                 self.state.next_preview_match();
             }
@@ -373,34 +802,137 @@ impl App {
                 // If sections are selected, copy those; otherwise copy current
                 if let Some(content) = self.state.get_selected_sections_content() {
                     let count = self.state.preview_selected_sections.len();
-                    if copy_to_clipboard(&content) {
-                        self.state
-                            .set_status(format!("Copied {} section(s) to clipboard", count));
-                        self.state.clear_section_selection();
-                    } else {
-                        self.state
-                            .set_status("Failed to copy (clipboard tool not found?)".to_string());
+                    match copy_to_clipboard(&content) {
+                        Ok(backend) => {
+                            self.state.set_status(format!(
+                                "Copied {} section(s) via {}",
+                                count, backend
+                            ));
+                            self.state.clear_section_selection();
+                        }
+                        Err(e) => self.state.set_status(e.to_string()),
                     }
                 } else if let Some(content) = self.state.get_current_section_content() {
-                    if copy_to_clipboard(&content) {
-                        let section_idx = self.state.current_section_index().unwrap_or(0) + 1;
-                        self.state
-                            .set_status(format!("Copied section {} to clipboard", section_idx));
-                    } else {
-                        self.state
-                            .set_status("Failed to copy (clipboard tool not found?)".to_string());
+                    match copy_to_clipboard(&content) {
+                        Ok(backend) => {
+                            let section_idx = self.state.current_section_index().unwrap_or(0) + 1;
+                            self.state.set_status(format!(
+                                "Copied section {} via {}",
+                                section_idx, backend
+                            ));
+                        }
+                        Err(e) => self.state.set_status(e.to_string()),
+                    }
+                }
+            }
+            // Toggle wrapping; Left/Right scroll horizontally when off
+            KeyCode::Char('w') => {
+                self.state.preview_wrap = !self.state.preview_wrap;
+                self.state.preview_hscroll = 0;
+                let status = if self.state.preview_wrap {
+                    "Wrap: on".to_string()
+                } else {
+                    let keys = self.state.render_options.symbols().left_right;
+                    format!("Wrap: off ({} to scroll)", keys)
+                };
+                self.state.set_status(status);
+            }
+            // Toggle Markdown styling
+            KeyCode::Char('m') => {
+                self.state.preview_markdown = !self.state.preview_markdown;
+                self.state.set_status(format!(
+                    "Markdown: {}",
+                    if self.state.preview_markdown { "styled" } else { "raw" }
+                ));
+            }
+            KeyCode::Left | KeyCode::Char('h') if !self.state.preview_wrap => {
+                self.state.preview_hscroll = self.state.preview_hscroll.saturating_sub(8);
+            }
+            KeyCode::Right | KeyCode::Char('l') if !self.state.preview_wrap => {
+                self.state.preview_hscroll += 8;
+            }
+            // Copy the message under the cursor (without its header)
+            KeyCode::Char('y') => match self.state.get_message_at_cursor() {
+                Some(content) => match copy_to_clipboard(&content) {
+                    Ok(backend) => {
+                        self.state.set_status(format!(
+                            "Copied message ({} lines) via {}",
+                            content.lines().count(),
+                            backend
+                        ));
+                    }
+                    Err(e) => self.state.set_status(e.to_string()),
+                },
+                None => self.state.set_status("No message under cursor".to_string()),
+            },
+            // Copy session as rendered Markdown
+            KeyCode::Char('e') => {
+                if let Some(session) = self.state.current_session() {
+                    match actions::export_session_to_string(session, &self.state.render_options) {
+                        Ok(markdown) => {
+                            match copy_to_clipboard(&markdown) {
+                                Ok(backend) => {
+                                    self.state.set_status(format!(
+                                        "Copied Markdown via {} ({} bytes, {} chars)",
+                                        backend,
+                                        markdown.len(),
+                                        markdown.chars().count()
+                                    ));
+                                }
+                                Err(e) => self.state.set_status(e.to_string()),
+                            }
+                        }
+                        Err(e) => {
+                            self.state.set_status(format!("Export failed: {}", e));
+                        }
                     }
                 }
             }
+            // Toggle thinking blocks
+            KeyCode::Char('t') => {
+                self.state.render_options.thinking = !self.state.render_options.thinking;
+                self.reload_preview();
+                self.state.set_status(format!(
+                    "Thinking: {}",
+                    if self.state.render_options.thinking { "shown" } else { "hidden" }
+                ));
+            }
+            // Toggle tool calls and results
+            KeyCode::Char('T') => {
+                self.state.render_options.tools = !self.state.render_options.tools;
+                self.reload_preview();
+                self.state.set_status(format!(
+                    "Tools: {}",
+                    if self.state.render_options.tools { "shown" } else { "hidden" }
+                ));
+            }
+            // Toggle ANSI escape stripping
+            KeyCode::Char('x') => {
+                self.state.render_options.strip_ansi = !self.state.render_options.strip_ansi;
+                self.reload_preview();
+                self.state.set_status(format!(
+                    "ANSI escape codes: {}",
+                    if self.state.render_options.strip_ansi { "stripped" } else { "kept" }
+                ));
+            }
+            // Toggle full (untruncated) tool inputs and results
+            KeyCode::Char('E') => {
+                self.state.render_options.full_tools = !self.state.render_options.full_tools;
+                self.reload_preview();
+                self.state.set_status(format!(
+                    "Tool output: {}",
+                    if self.state.render_options.full_tools { "full" } else { "truncated" }
+                ));
+            }
             // Copy entire preview
             KeyCode::Char('C') => {
                 let content = self.state.get_full_preview_content();
-                if copy_to_clipboard(&content) {
-                    self.state
-                        .set_status("Copied entire session to clipboard".to_string());
-                } else {
-                    self.state
-                        .set_status("Failed to copy (clipboard tool not found?)".to_string());
+                match copy_to_clipboard(&content) {
+                    Ok(backend) => {
+                        self.state
+                            .set_status(format!("Copied entire session via {}", backend));
+                    }
+                    Err(e) => self.state.set_status(e.to_string()),
                 }
             }
             _ => {}
@@ -422,6 +954,8 @@ impl App {
                 self.state.filter.query.pop();
                 self.state.apply_filters();
             }
+            KeyCode::Tab => self.state.toggle_whole_word(),
+            KeyCode::BackTab => self.state.toggle_fuzzy(),
             KeyCode::Char(c) => {
                 self.state.filter.query.push(c);
                 self.state.apply_filters();
@@ -430,11 +964,92 @@ impl App {
         }
     }
 
-    fn handle_help_keys(&mut self, code: KeyCode) {
+    fn handle_picker_keys(&mut self, code: KeyCode) {
+        let count = self.state.project_picker_entries().len();
         match code {
-            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('?') => {
+            KeyCode::Esc => self.state.view = View::List,
+            KeyCode::Enter => {
+                self.state.select_picker_entry();
+                self.state.cursor = 0;
+                self.table_state.select(Some(0));
+            }
+            KeyCode::Down if self.state.picker_cursor + 1 < count => {
+                self.state.picker_cursor += 1;
+            }
+            KeyCode::Up => {
+                self.state.picker_cursor = self.state.picker_cursor.saturating_sub(1);
+            }
+            KeyCode::Backspace => {
+                self.state.picker_query.pop();
+                self.state.picker_cursor = 0;
+            }
+            KeyCode::Char(c) => {
+                self.state.picker_query.push(c);
+                self.state.picker_cursor = 0;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_archive_name_keys(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Esc => self.state.view = View::List,
+            KeyCode::Enter => {
                 self.state.view = View::List;
+                self.do_batch_archive();
             }
+            KeyCode::Backspace => {
+                self.state.archive_name.pop();
+            }
+            KeyCode::Char(c) => self.state.archive_name.push(c),
+            _ => {}
+        }
+    }
+
+    fn handle_diff_keys(&mut self, code: KeyCode) {
+        let max_scroll = self.state.diff_ops.len().saturating_sub(1);
+        let page = self.state.visible_height;
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => {
+                self.state.diff_ops.clear();
+                self.state.view = View::List;
+            }
+            KeyCode::Char('?') => self.state.open_help(),
+            KeyCode::Char('j') | KeyCode::Down => {
+                self.state.diff_scroll = (self.state.diff_scroll + 1).min(max_scroll);
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.state.diff_scroll = self.state.diff_scroll.saturating_sub(1);
+            }
+            KeyCode::PageDown => {
+                self.state.diff_scroll = (self.state.diff_scroll + page).min(max_scroll);
+            }
+            KeyCode::PageUp => {
+                self.state.diff_scroll = self.state.diff_scroll.saturating_sub(page);
+            }
+            KeyCode::Char('g') | KeyCode::Home => self.state.diff_scroll = 0,
+            KeyCode::Char('G') | KeyCode::End => self.state.diff_scroll = max_scroll,
+            _ => {}
+        }
+    }
+
+    fn handle_help_keys(&mut self, code: KeyCode) {
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc | KeyCode::Char('?') | KeyCode::F(1) => {
+                self.state.close_help();
+            }
+            // Scrolling past the end is clamped when the overlay is drawn
+            KeyCode::Char('j') | KeyCode::Down => self.state.help_scroll += 1,
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.state.help_scroll = self.state.help_scroll.saturating_sub(1);
+            }
+            KeyCode::PageDown => self.state.help_scroll += self.state.help_page.max(1),
+            KeyCode::PageUp => {
+                let page = self.state.help_page;
+                self.state.help_scroll = self.state.help_scroll.saturating_sub(page);
+            }
+            KeyCode::Char('g') | KeyCode::Home => self.state.help_scroll = 0,
+            KeyCode::Char('G') | KeyCode::End => self.state.help_scroll = usize::MAX,
             _ => {}
         }
     }
@@ -455,70 +1070,345 @@ impl App {
     }
 
     fn load_current_metadata(&mut self) {
+        let chars_per_token = self.state.chars_per_token;
         if let Some(idx) = self.state.current_session_index() {
             if let Some(session) = self.state.sessions.get_mut(idx) {
                 if session.first_message.is_none() {
-                    let _ = load_session_metadata(session);
+                    let _ = load_session_metadata(session, chars_per_token);
                 }
             }
         }
     }
 
+    /// Compare the user messages of the two selected sessions
+    fn open_diff(&mut self) {
+        let mut sessions = self.state.get_selected_sessions();
+        if sessions.len() != 2 {
+            self.state
+                .set_status("Select exactly 2 sessions to compare".to_string());
+            return;
+        }
+        // Older session on the left
+        sessions.sort_by_key(|s| s.modified);
+        let (left, right) = (sessions[0], sessions[1]);
+
+        let load = |s: &crate::session::Session| {
+            load_session_messages(&s.path, &self.state.render_options)
+        };
+        let (left_messages, right_messages) = match (load(left), load(right)) {
+            (Ok(l), Ok(r)) => (l, r),
+            (Err(e), _) | (_, Err(e)) => {
+                self.state.set_status(format!("Compare failed: {}", e));
+                return;
+            }
+        };
+
+        let ops = diff_user_messages(&left_messages, &right_messages);
+        let shared = shared_prefix_len(&ops);
+        let count = |ops: &[DiffOp], left_side: bool| {
+            ops.iter()
+                .filter(|op| match op {
+                    DiffOp::Same(_) => true,
+                    DiffOp::Left(_) => left_side,
+                    DiffOp::Right(_) => !left_side,
+                })
+                .count()
+        };
+        let local = self.state.render_options.local_time;
+        let describe = |marker: &str, s: &crate::session::Session, users: usize, total: usize| {
+            format!(
+                "{} {} ({}, {} user / {} messages)",
+                marker,
+                s.id,
+                format_time(s.modified, "%Y-%m-%d %H:%M", local),
+                users,
+                total
+            )
+        };
+
+        let same = ops.iter().all(|op| matches!(op, DiffOp::Same(_)));
+        self.state.diff_header = vec![
+            describe("-", left, count(&ops, true), left_messages.len()),
+            describe("+", right, count(&ops, false), right_messages.len()),
+            if same {
+                "User messages are identical".to_string()
+            } else {
+                format!("{} shared user message(s) before diverging", shared)
+            },
+        ];
+        self.state.diff_ops = ops;
+        self.state.diff_scroll = 0;
+        self.state.view = View::Diff;
+    }
+
+    /// Start loading the current session's preview on a background thread
+    ///
+    /// Small files finish within `PREVIEW_SPINNER_DELAY` and open right away; larger ones
+    /// show a spinner over the list until `poll_preview_load` picks up the result.
     fn open_preview(&mut self) {
+        let Some(session) = self.state.current_session() else {
+            return;
+        };
+        let path = session.path.clone();
+        let options = self.state.render_options;
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            // The receiver is gone if the load was canceled
+            let _ = tx.send(load_session_messages(&path, &options));
+        });
+
+        match rx.recv_timeout(PREVIEW_SPINNER_DELAY) {
+            Ok(result) => self.finish_preview_load(result),
+            Err(RecvTimeoutError::Timeout) => {
+                self.preview_load = Some(PreviewLoad {
+                    rx,
+                    started: Instant::now(),
+                })
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                self.state.set_status("Failed to load: loader stopped".to_string())
+            }
+        }
+    }
+
+    /// Open the preview if the background load has finished
+    fn poll_preview_load(&mut self) {
+        let Some(ref load) = self.preview_load else {
+            return;
+        };
+        let result = match load.rx.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Err(anyhow::anyhow!("loader stopped")),
+        };
+        self.preview_load = None;
+        self.finish_preview_load(result);
+    }
+
+    fn finish_preview_load(&mut self, result: Result<Vec<DisplayMessage>>) {
+        match result {
+            Ok(messages) => self.set_preview_messages(&messages),
+            Err(e) => {
+                self.state.set_status(format!("Failed to load: {}", e));
+                return;
+            }
+        }
+        self.state.jump_preview_to(0);
+        // Carry a list search into the preview so it opens at the first match
+        if !self.state.filter.query.is_empty() {
+            self.state.preview_search = self.state.filter.query.clone();
+            self.state.update_preview_search();
+        }
+        self.state.view = View::Preview;
+    }
+
+    /// Re-render the preview after changing render options, keeping position
+    fn reload_preview(&mut self) {
+        if self.load_preview_lines() {
+            self.state.preview_scroll = self
+                .state
+                .preview_scroll
+                .min(self.state.preview_lines.len().saturating_sub(1));
+            let cursor = self.state.preview_cursor;
+            self.state.set_preview_cursor(cursor);
+            self.state.update_preview_search();
+        }
+    }
+
+    /// Build preview lines for the current session, returning false on failure
+    fn load_preview_lines(&mut self) -> bool {
         if let Some(session) = self.state.current_session() {
-            match load_session_messages(&session.path) {
+            match load_session_messages(&session.path, &self.state.render_options) {
                 Ok(messages) => {
-                    self.state.preview_lines = messages
-                        .iter()
-                        .flat_map(|msg| {
-                            let role = match msg.role {
-                                crate::session::MessageRole::User => "[User]",
-                                crate::session::MessageRole::Assistant => "[Assistant]",
-                                crate::session::MessageRole::System => "[System]",
-                            };
-                            let header =
-                                format!("{} {}", role, msg.timestamp.format("%Y-%m-%d %H:%M:%S"));
-                            let mut lines = vec![header, String::new()];
-                            lines.extend(msg.content.lines().map(String::from));
-                            lines.push(String::new());
-                            lines
-                        })
-                        .collect();
-                    // Parse code blocks for syntax highlighting
-                    self.code_blocks = parse_code_blocks(&self.state.preview_lines);
-                    self.state.preview_scroll = 0;
-                    self.state.view = View::Preview;
+                    self.set_preview_messages(&messages);
+                    return true;
                 }
                 Err(e) => {
                     self.state.set_status(format!("Failed to load: {}", e));
                 }
             }
         }
+        false
+    }
+
+    fn set_preview_messages(&mut self, messages: &[DisplayMessage]) {
+        let local = self.state.render_options.local_time;
+        self.state.preview_lines = messages
+            .iter()
+            .flat_map(|msg| {
+                let role = match msg.role {
+                    crate::session::MessageRole::User => "[User]",
+                    crate::session::MessageRole::Assistant => "[Assistant]",
+                    crate::session::MessageRole::System => "[System]",
+                };
+                let time = format_time(msg.timestamp, "%Y-%m-%d %H:%M:%S", local);
+                let header = format!("{} {}", role, time);
+                let mut lines = vec![header, String::new()];
+                lines.extend(msg.content.lines().map(String::from));
+                lines.push(String::new());
+                lines
+            })
+            .collect();
+        // Parse code blocks for syntax highlighting
+        self.code_blocks = parse_code_blocks(&self.state.preview_lines);
     }
 
     fn confirm_delete(&mut self) {
-        let count = if self.state.selected.is_empty() {
-            1
+        let mut targets: Vec<usize> = if self.state.selected.is_empty() {
+            self.state.current_session_index().into_iter().collect()
         } else {
-            self.state.selected.len()
+            self.state.selected.iter().copied().collect()
         };
+        if targets.is_empty() {
+            return;
+        }
+        targets.sort_unstable();
 
-        let msg = if count == 1 {
+        let mut msg = if targets.len() == 1 {
             "Delete this session? (y/n)".to_string()
         } else {
-            format!("Delete {} sessions? (y/n)", count)
+            format!(
+                "Delete {} sessions ({})? (y/n)",
+                targets.len(),
+                self.total_size(&targets)
+            )
         };
+        let minutes = self.state.protect_recent_minutes;
+        let recent = targets
+            .iter()
+            .filter_map(|&idx| self.state.sessions.get(idx))
+            .filter(|s| actions::is_recently_active(s, minutes))
+            .count();
+        if recent > 0 {
+            msg.push_str(&format!(
+                "\n{} {} modified in the last {} min, may still be in use",
+                self.state.render_options.symbols().warning,
+                recent,
+                minutes
+            ));
+        }
 
-        self.state.show_confirm(msg, DialogAction::DeleteSelected);
+        self.state
+            .show_confirm_with_targets(msg, DialogAction::DeleteSelected, targets);
     }
 
     fn confirm_delete_older(&mut self) {
-        // For simplicity, hardcode 30 days
-        let days = 30;
-        self.state.show_confirm(
-            format!("Delete sessions older than {} days? (y/n)", days),
-            DialogAction::DeleteOlderThan(days),
+        let days = self.state.older_than_days;
+        let now = chrono::Utc::now();
+        let targets: Vec<usize> = self
+            .state
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| now.signed_duration_since(s.modified).num_days() >= days as i64)
+            .map(|(idx, _)| idx)
+            .collect();
+        if targets.is_empty() {
+            self.state
+                .set_status(format!("No sessions older than {} days", days));
+            return;
+        }
+
+        let msg = format!(
+            "Delete {} session(s) older than {} days ({})? (y/n)",
+            targets.len(),
+            days,
+            self.total_size(&targets)
+        );
+        self.state
+            .show_confirm_with_targets(msg, DialogAction::DeleteOlderThan(days), targets);
+    }
+
+    /// Combined size of the sessions at `indices`, human readable
+    fn total_size(&self, indices: &[usize]) -> String {
+        let bytes: u64 = indices
+            .iter()
+            .filter_map(|&idx| self.state.sessions.get(idx))
+            .map(|s| s.size_bytes)
+            .sum();
+        humansize::format_size(bytes, humansize::BINARY)
+    }
+
+    fn confirm_export(&mut self) {
+        let format = self.state.export_format;
+        let template = &self.state.export_name_template;
+        let targets = self.state.action_targets();
+        let names: Vec<String> = targets
+            .iter()
+            .map(|s| actions::export_file_name(s, format, template))
+            .collect();
+        let note = sidecar_note(&targets, "not exported", &self.state.render_options.symbols());
+
+        if names.is_empty() {
+            self.state.set_status("No sessions to export".to_string());
+            return;
+        }
+
+        match actions::get_export_dir(self.state.export_dir.as_deref()) {
+            Ok(dir) => {
+                let msg = format_target_list("Export", &names, &dir, note.as_deref());
+                self.state.show_confirm(msg, DialogAction::ExportSelected);
+            }
+            Err(e) => {
+                self.state.set_status(format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    /// Confirm merging the selected sessions (a resume chain) into one export
+    fn confirm_merge_export(&mut self) {
+        let sessions = self.state.get_selected_sessions();
+        if sessions.len() < 2 {
+            self.state
+                .set_status("Select the 2+ sessions of a resume chain to merge".to_string());
+            return;
+        }
+        let name = actions::merged_file_name(
+            &sessions,
+            self.state.export_format,
+            &self.state.export_name_template,
         );
+        let count = sessions.len();
+
+        match actions::get_export_dir(self.state.export_dir.as_deref()) {
+            Ok(dir) => {
+                let msg = [
+                    format!("Merge {} sessions into one export in {}?", count, dir.display()),
+                    format!("  {}", name),
+                    "Repeated messages are kept once".to_string(),
+                    "(y/n)".to_string(),
+                ]
+                .join("\n");
+                self.state.show_confirm(msg, DialogAction::ExportMerged);
+            }
+            Err(e) => {
+                self.state.set_status(format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    fn confirm_archive(&mut self) {
+        let targets = self.state.action_targets();
+        let names: Vec<String> = targets
+            .iter()
+            .map(|s| actions::archive_file_name(s))
+            .collect();
+        let note = sidecar_note(&targets, "included", &self.state.render_options.symbols());
+
+        if names.is_empty() {
+            self.state.set_status("No sessions to archive".to_string());
+            return;
+        }
+
+        match actions::get_default_archive_dir() {
+            Ok(dir) => {
+                let msg = format_target_list("Archive", &names, &dir, note.as_deref());
+                self.state.show_confirm(msg, DialogAction::ArchiveSelected);
+            }
+            Err(e) => {
+                self.state.set_status(format!("Archive failed: {}", e));
+            }
+        }
     }
 
     fn execute_dialog_action(&mut self, action: DialogAction) {
@@ -576,120 +1466,526 @@ impl App {
                     count, days
                 ));
             }
+            DialogAction::ExportSelected => self.do_export(),
+            DialogAction::ExportMerged => self.do_merge_export(),
+            DialogAction::UndoLastAction => self.do_undo(),
+            DialogAction::ArchiveSelected => self.do_archive(),
+        }
+    }
+
+    fn do_export(&mut self) {
+        let sessions = self.state.action_targets();
+
+        if sessions.is_empty() {
+            self.state.set_status("No sessions to export".to_string());
+            return;
+        }
+
+        match actions::get_export_dir(self.state.export_dir.as_deref()) {
+            Ok(dir) => {
+                let mut exported = Vec::new();
+                let mut created = Vec::new();
+                for session in sessions {
+                    let (format, template) =
+                        (self.state.export_format, &self.state.export_name_template);
+                    let existed = dir
+                        .join(actions::export_file_name(session, format, template))
+                        .exists();
+                    if let Ok(path) = actions::export_session(
+                        session,
+                        &dir,
+                        self.state.export_format,
+                        &self.state.render_options,
+                        &self.state.export_name_template,
+                    ) {
+                        if !existed {
+                            created.push(path.clone());
+                        }
+                        exported.push((session, path));
+                    }
+                }
+                let count = exported.len();
+                match actions::update_export_index(&dir, &exported) {
+                    Ok(_) => self.state.set_status(format!(
+                        "Exported {} session(s) to {:?} (L opens it)",
+                        count, dir
+                    )),
+                    Err(e) => self.state.set_status(format!(
+                        "Exported {} session(s) to {:?} (index not updated: {})",
+                        count, dir, e
+                    )),
+                }
+                self.state.last_output_dir = Some(dir.clone());
+                self.state.last_action = Some(ActionRecord {
+                    description: format!("export of {} session(s)", count),
+                    created,
+                    export_dir: Some(dir),
+                });
+            }
+            Err(e) => {
+                self.state.set_status(format!("Export failed: {}", e));
+            }
+        }
+    }
+
+    fn do_merge_export(&mut self) {
+        let sessions = self.state.get_selected_sessions();
+        let result = actions::get_export_dir(self.state.export_dir.as_deref()).and_then(|dir| {
+            let name = actions::merged_file_name(
+                &sessions,
+                self.state.export_format,
+                &self.state.export_name_template,
+            );
+            let existed = dir.join(name).exists();
+            let path = actions::export_merged_sessions(
+                &sessions,
+                &dir,
+                self.state.export_format,
+                &self.state.render_options,
+                &self.state.export_name_template,
+            )?;
+            // Indexed under the newest session, which the chain continues into
+            if let Some(newest) = sessions.iter().max_by_key(|s| s.modified) {
+                actions::update_export_index(&dir, &[(*newest, path.clone())])?;
+            }
+            Ok((dir, path, existed))
+        });
+
+        let count = sessions.len();
+        match result {
+            Ok((dir, path, existed)) => {
+                self.state.set_status(format!(
+                    "Merged {} sessions into {:?} (L opens the folder)",
+                    count, path
+                ));
+                self.state.last_output_dir = Some(dir.clone());
+                self.state.last_action = Some(ActionRecord {
+                    description: format!("merged export of {} sessions", count),
+                    created: if existed { Vec::new() } else { vec![path] },
+                    export_dir: Some(dir),
+                });
+            }
+            Err(e) => self.state.set_status(format!("Merge failed: {}", e)),
+        }
+    }
+
+    fn do_archive(&mut self) {
+        let sessions = self.state.action_targets();
+
+        if sessions.is_empty() {
+            self.state.set_status("No sessions to archive".to_string());
+            return;
+        }
+
+        match actions::get_default_archive_dir() {
+            Ok(dir) => {
+                let mut count = 0;
+                let mut created = Vec::new();
+                for session in sessions {
+                    let existed = dir.join(actions::archive_file_name(session)).exists();
+                    if let Ok(path) = actions::archive_session(session, &dir) {
+                        count += 1;
+                        if !existed {
+                            created.push(path);
+                        }
+                    }
+                }
+                self.state.set_status(format!(
+                    "Archived {} session(s) to {:?} (L opens it)",
+                    count, dir
+                ));
+                self.state.last_output_dir = Some(dir);
+                self.state.last_action = Some(ActionRecord {
+                    description: format!("archive of {} session(s)", count),
+                    created,
+                    export_dir: None,
+                });
+            }
+            Err(e) => {
+                self.state.set_status(format!("Archive failed: {}", e));
+            }
+        }
+    }
+
+    /// Archive the selection (or current session) into one named tar.gz
+    fn do_batch_archive(&mut self) {
+        let sessions = self.state.action_targets();
+        let result = actions::get_default_archive_dir().and_then(|dir| {
+            let path = actions::archive_batch(&sessions, &dir, &self.state.archive_name)?;
+            Ok((dir, path))
+        });
+        let count = sessions.len();
+        match result {
+            Ok((dir, path)) => {
+                self.state.set_status(format!(
+                    "Archived {} session(s) to {:?} (L opens the folder)",
+                    count, path
+                ));
+                self.state.last_output_dir = Some(dir);
+                self.state.last_action = Some(ActionRecord {
+                    description: format!("archive of {} session(s)", count),
+                    created: vec![path],
+                    export_dir: None,
+                });
+            }
+            Err(e) => self.state.set_status(format!("Archive failed: {}", e)),
+        }
+    }
+
+    /// Describe what undoing the last export or archive will remove, and ask first
+    fn confirm_undo(&mut self) {
+        let Some(ref record) = self.state.last_action else {
+            self.state.set_status(
+                "Nothing to undo (only exports and archives can be undone)".to_string(),
+            );
+            return;
+        };
+        if record.created.is_empty() {
+            self.state.set_status(format!(
+                "The last {} only overwrote existing files; nothing to undo",
+                record.description
+            ));
+            return;
+        }
+
+        const MAX_SHOWN: usize = 5;
+        let mut lines = vec![format!(
+            "Undo the last {}? This deletes {} file(s):",
+            record.description,
+            record.created.len()
+        )];
+        lines.extend(
+            record
+                .created
+                .iter()
+                .take(MAX_SHOWN)
+                .map(|path| format!("  {}", path.display())),
+        );
+        if record.created.len() > MAX_SHOWN {
+            lines.push(format!("  ...and {} more", record.created.len() - MAX_SHOWN));
+        }
+        lines.push("(y/n)".to_string());
+        self.state
+            .show_confirm(lines.join("\n"), DialogAction::UndoLastAction);
+    }
+
+    fn do_undo(&mut self) {
+        let Some(record) = self.state.last_action.take() else {
+            return;
+        };
+        let mut removed = 0;
+        let mut failed = 0;
+        for path in &record.created {
+            match std::fs::remove_file(path) {
+                Ok(()) => removed += 1,
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                Err(_) => failed += 1,
+            }
+        }
+        // Drops the index entries of the removed files
+        if let Some(ref dir) = record.export_dir {
+            let _ = actions::update_export_index(dir, &[]);
+        }
+
+        let mut msg = format!("Undid {}: removed {} file(s)", record.description, removed);
+        if failed > 0 {
+            msg.push_str(&format!(", {} could not be removed", failed));
+        }
+        self.state.set_status(msg);
+    }
+
+    /// Resume the current session with `claude --resume`: in a new terminal window if one
+    /// is configured, otherwise by quitting so main can run it in this terminal
+    fn resume_session(&mut self) {
+        let Some(session) = self.state.get_current_session() else {
+            return;
+        };
+        let dir = PathBuf::from(&session.project_path);
+        let id = session.id.clone();
+        if !dir.is_dir() {
+            self.state
+                .set_status(format!("Directory {} no longer exists", dir.display()));
+            return;
+        }
+
+        let Some(ref terminal) = self.state.terminal_command else {
+            self.resume_on_exit = Some((dir, id));
+            self.should_quit = true;
+            return;
+        };
+
+        let parts: Vec<&str> = terminal.split_whitespace().collect();
+        let Some((program, args)) = parts.split_first() else {
+            return;
+        };
+        let result = Command::new(program)
+            .args(args)
+            .args(["claude", "--resume", &id])
+            .current_dir(&dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match result {
+            Ok(_) => self
+                .state
+                .set_status(format!("Resuming {} in {}", id, program)),
+            Err(e) => self
+                .state
+                .set_status(format!("Failed to run {}: {}", program, e)),
+        }
+    }
+
+    /// Open a directory in the editor (suspending the TUI) or the file manager
+    fn open_directory(
+        &mut self,
+        terminal: &mut ratatui::Terminal<impl Backend>,
+        dir: &Path,
+    ) -> Result<()> {
+        let (command, in_terminal) = open_dir_command();
+        let Some((program, args)) = command.split_first() else {
+            return Ok(());
+        };
+
+        let result = if in_terminal {
+            run_suspended(terminal, Command::new(program).args(args).arg(dir))?
+        } else {
+            Command::new(program)
+                .args(args)
+                .arg(dir)
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .spawn()
+                .map(|_| ())
+        };
+
+        match result {
+            Ok(()) => self
+                .state
+                .set_status(format!("Opened {} with {}", dir.display(), program)),
+            Err(e) => self
+                .state
+                .set_status(format!("Failed to run {}: {}", program, e)),
+        }
+        Ok(())
+    }
+
+    /// Open the directory of the last export or archive in the file manager
+    fn open_last_output_dir(&mut self) {
+        let Some(dir) = self.state.last_output_dir.clone() else {
+            self.state
+                .set_status("Nothing exported or archived yet".to_string());
+            return;
+        };
+        if !dir.is_dir() {
+            self.state
+                .set_status(format!("Directory no longer exists: {}", dir.display()));
+            return;
+        }
+
+        let program = file_manager_command();
+        let result = Command::new(program)
+            .arg(&dir)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn();
+        match result {
+            Ok(_) => self
+                .state
+                .set_status(format!("Opened {} with {}", dir.display(), program)),
+            Err(e) => self
+                .state
+                .set_status(format!("Failed to run {}: {}", program, e)),
         }
     }
 
-    fn do_export(&mut self) {
-        let sessions: Vec<_> = if self.state.selected.is_empty() {
-            self.state.current_session().into_iter().collect()
-        } else {
-            self.state.get_selected_sessions()
+    /// Show a session's raw JSONL in a pager or editor (suspending the TUI)
+    fn open_raw_file(
+        &mut self,
+        terminal: &mut ratatui::Terminal<impl Backend>,
+        path: &Path,
+    ) -> Result<()> {
+        let command = open_file_command();
+        let Some((program, args)) = command.split_first() else {
+            return Ok(());
         };
 
-        if sessions.is_empty() {
-            self.state.set_status("No sessions to export".to_string());
-            return;
+        if let Err(e) = run_suspended(terminal, Command::new(program).args(args).arg(path))? {
+            self.state
+                .set_status(format!("Failed to run {}: {}", program, e));
         }
+        Ok(())
+    }
 
-        match actions::get_default_export_dir() {
-            Ok(dir) => {
-                let mut count = 0;
-                for session in sessions {
-                    if actions::export_session_markdown(session, &dir).is_ok() {
-                        count += 1;
-                    }
+    fn do_refresh(&mut self, terminal: &mut ratatui::Terminal<impl Backend>) -> Result<()> {
+        let rebuild = std::mem::take(&mut self.rebuild_cache);
+        if rebuild {
+            if let Err(e) = SearchIndex::remove_file() {
+                self.state.set_status(format!("Rebuild failed: {}", e));
+                return Ok(());
+            }
+            self.state.search_index = SearchIndex::load();
+        }
+
+        let projects_dir = &self.state.projects_dir;
+        match crate::session::scan_sessions(projects_dir, self.state.follow_symlinks) {
+            Ok(scan) => {
+                let theme = self.state.color_theme.theme();
+                let label = if rebuild { "Rebuilding cache" } else { "Refreshing" };
+                let (merge, total) =
+                    self.merge_and_reparse(scan.sessions, rebuild, |done, total| {
+                        // Update progress display
+                        if done % 20 == 1 || done == total {
+                            terminal.draw(|f| draw_progress(f, theme, label, done, total))?;
+                        }
+                        Ok(())
+                    })?;
+                let mut status = format!(
+                    "{}: {} sessions (+{} new, -{} removed, {} reparsed)",
+                    if rebuild { "Rebuilt cache" } else { "Refreshed" },
+                    self.state.sessions.len(),
+                    merge.added,
+                    merge.removed,
+                    total
+                );
+                if let Some(first) = scan.skipped.first() {
+                    status.push_str(&format!(
+                        "; skipped {} unreadable project dir(s), e.g. {}",
+                        scan.skipped.len(),
+                        first
+                    ));
                 }
-                self.state
-                    .set_status(format!("Exported {} session(s) to {:?}", count, dir));
+                self.state.set_status(status);
             }
             Err(e) => {
-                self.state.set_status(format!("Export failed: {}", e));
+                self.state.set_status(format!("Refresh failed: {}", e));
             }
         }
+        Ok(())
     }
 
-    fn do_archive(&mut self) {
-        let sessions: Vec<_> = if self.state.selected.is_empty() {
-            self.state.current_session().into_iter().collect()
+    /// Merge a rescan into the list and reparse new or changed sessions (all of them when
+    /// `reparse_all`), then refilter and re-sort on the fresh metadata
+    ///
+    /// `progress` is called with (done, total) after each session. Returns the merge counts
+    /// and the number of sessions reparsed.
+    fn merge_and_reparse(
+        &mut self,
+        sessions: Vec<Session>,
+        reparse_all: bool,
+        mut progress: impl FnMut(usize, usize) -> Result<()>,
+    ) -> Result<(MergeResult, usize)> {
+        let merge = self.state.merge_sessions(sessions);
+        let stale: Vec<usize> = if reparse_all {
+            (0..self.state.sessions.len()).collect()
         } else {
-            self.state.get_selected_sessions()
+            merge.stale.clone()
         };
 
-        if sessions.is_empty() {
-            self.state.set_status("No sessions to archive".to_string());
-            return;
-        }
-
-        match actions::get_default_archive_dir() {
-            Ok(dir) => {
-                let mut count = 0;
-                for session in sessions {
-                    if actions::archive_session(session, &dir).is_ok() {
-                        count += 1;
-                    }
-                }
-                self.state
-                    .set_status(format!("Archived {} session(s) to {:?}", count, dir));
-            }
-            Err(e) => {
-                self.state.set_status(format!("Archive failed: {}", e));
+        let chars_per_token = self.state.chars_per_token;
+        for (i, &idx) in stale.iter().enumerate() {
+            if let Some(session) = self.state.sessions.get_mut(idx) {
+                let _ = load_session_metadata(session, chars_per_token);
             }
+            progress(i + 1, stale.len())?;
         }
+
+        self.state.apply_filters();
+        self.table_state.select(Some(self.state.cursor));
+        self.state.update_search_index();
+        Ok((merge, stale.len()))
     }
 
-    fn do_refresh(&mut self, terminal: &mut ratatui::Terminal<impl Backend>) -> Result<()> {
-        match crate::session::scan_sessions() {
-            Ok(sessions) => {
-                let total = sessions.len();
-                self.state = UiState::new(sessions);
-                self.table_state.select(Some(0));
+    /// Rescan for `--watch`, applying changes once they settle
+    ///
+    /// Claude appends to a live session constantly, so a change is applied when two polls
+    /// agree, or after `WATCH_MAX_DELAY` for a file that never stops growing.
+    fn poll_watch(&mut self) {
+        self.last_watch_poll = Instant::now();
+        let projects_dir = &self.state.projects_dir;
+        let Ok(scan) = crate::session::scan_sessions(projects_dir, self.state.follow_symlinks) else {
+            return;
+        };
+        let fresh = scan.sessions;
 
-                // Load all metadata with progress display
-                for (i, session) in self.state.sessions.iter_mut().enumerate() {
-                    if session.first_message.is_none() {
-                        let _ = load_session_metadata(session);
-                    }
+        let snapshot = fingerprint(&fresh);
+        if snapshot == fingerprint(&self.state.sessions) {
+            self.pending_watch = None;
+            return;
+        }
 
-                    // Update progress display
-                    if i % 20 == 0 || i == total - 1 {
-                        terminal.draw(|f| {
-                            let area = f.size();
-                            let msg = format!("Refreshing... {}/{}", i + 1, total);
-                            let paragraph = ratatui::widgets::Paragraph::new(msg)
-                                .style(
-                                    ratatui::style::Style::default()
-                                        .fg(ratatui::style::Color::Cyan),
-                                )
-                                .alignment(ratatui::layout::Alignment::Center);
-                            f.render_widget(paragraph, area);
-                        })?;
-                    }
-                }
+        let since = match self.pending_watch.take() {
+            Some((prev, since)) if prev == snapshot || since.elapsed() >= WATCH_MAX_DELAY => None,
+            Some((_, since)) => Some(since),
+            None => Some(Instant::now()),
+        };
+        if let Some(since) = since {
+            self.pending_watch = Some((snapshot, since));
+            return;
+        }
 
-                self.state
-                    .set_status(format!("Refreshed: {} sessions", total));
-            }
-            Err(e) => {
-                self.state.set_status(format!("Refresh failed: {}", e));
-            }
+        let Ok((merge, _)) = self.merge_and_reparse(fresh, false, |_, _| Ok(())) else {
+            return;
+        };
+
+        if merge.added > 0 || merge.removed > 0 {
+            self.state.set_status(format!(
+                "Watch: +{} new, -{} removed",
+                merge.added, merge.removed
+            ));
         }
-        Ok(())
     }
 
     fn draw(&mut self, f: &mut Frame) {
+        let theme = self.state.color_theme.theme();
         let size = f.size();
 
+        // The fixed layout rows don't fit in a tiny pane
+        if size.width < MIN_WIDTH || size.height < MIN_HEIGHT {
+            let message = format!(
+                "Terminal too small (need at least {}x{})",
+                MIN_WIDTH, MIN_HEIGHT
+            );
+            f.render_widget(
+                Paragraph::new(message)
+                    .style(Style::default().fg(theme.highlight))
+                    .wrap(Wrap { trim: true }),
+                size,
+            );
+            // Nothing clickable is on screen
+            self.table_area = Rect::default();
+            return;
+        }
+
         match self.state.view {
-            View::List | View::Search => self.draw_list_view(f, size),
+            View::List | View::Search => {
+                self.draw_list_view(f, size);
+                self.draw_preview_spinner(f, size);
+            }
             View::Preview => self.draw_preview_view(f, size),
             View::Help => {
-                self.draw_list_view(f, size);
+                match self.state.help_context {
+                    View::Preview => self.draw_preview_view(f, size),
+                    View::Diff => self.draw_diff_view(f, size),
+                    _ => self.draw_list_view(f, size),
+                }
                 self.draw_help_overlay(f, size);
             }
             View::Confirm => {
                 self.draw_list_view(f, size);
                 self.draw_confirm_dialog(f, size);
             }
+            View::ProjectPicker => {
+                self.draw_list_view(f, size);
+                self.draw_project_picker(f, size);
+            }
+            View::ArchiveName => {
+                self.draw_list_view(f, size);
+                self.draw_archive_name_prompt(f, size);
+            }
+            View::Diff => self.draw_diff_view(f, size),
         }
     }
 
@@ -705,7 +2001,7 @@ impl App {
 
         // Update state with actual visible height (table area minus header row)
         self.state
-            .set_visible_height(chunks[1].height.saturating_sub(2) as usize);
+            .set_visible_height(chunks[1].height.saturating_sub(1) as usize);
 
         // Filter bar
         self.draw_header(f, chunks[0]);
@@ -718,43 +2014,101 @@ impl App {
     }
 
     fn draw_header(&mut self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let symbols = self.state.render_options.symbols();
         let filter_text = if self.state.view == View::Search {
-            format!("[{}▏]", self.state.filter.query)
+            format!("[{}{}]", self.state.filter.query, symbols.caret)
         } else if self.state.filter.query.is_empty() {
             "[/]".to_string()
         } else {
             format!("[{}]", self.state.filter.query)
         };
 
-        let project_text = format!("[{}]", self.state.current_project_filter());
-        let sort_arrow = if self.state.sort_reversed {
-            "↑"
+        let project_text = if self.state.filter.pinned_only {
+            format!("[{} {}]", self.state.current_project_filter(), symbols.pinned)
         } else {
-            "↓"
+            format!("[{}]", self.state.current_project_filter())
         };
+        let sort_arrow = symbols.sort_arrow(self.state.sort_reversed);
         let sort_text = format!("[{}{}]", self.state.sort_field.as_str(), sort_arrow);
 
-        let filter_line = Line::from(vec![
-            Span::raw(filter_text),
+        let mut spans = vec![Span::raw(filter_text)];
+        if self.state.filter.whole_word {
+            spans.push(Span::styled("[word]", Style::default().fg(theme.positive)));
+        }
+        if self.state.filter.fuzzy {
+            spans.push(Span::styled("[fuzzy]", Style::default().fg(theme.positive)));
+        }
+        spans.extend([
             Span::raw(" "),
-            Span::styled(project_text, Style::default().fg(Color::Yellow)),
+            Span::styled(project_text, Style::default().fg(theme.highlight)),
             Span::raw(" "),
-            Span::styled(sort_text, Style::default().fg(Color::Magenta)),
-            Span::raw(format!(
-                " ({}/{})",
-                self.state.filtered_indices.len(),
-                self.state.sessions.len()
-            )),
+            Span::styled(sort_text, Style::default().fg(theme.sort)),
         ]);
+        if self.state.filter.code_only {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled("[code]", Style::default().fg(theme.positive)));
+        }
+        if let Some(days) = self.state.filter.active_within_days {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!("[active {}d]", days),
+                Style::default().fg(theme.positive),
+            ));
+        }
+        let date_range = self.state.filter.date_range_label();
+        if !date_range.is_empty() {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!("[{}]", date_range),
+                Style::default().fg(theme.accent),
+            ));
+        }
+        let size_range = self.state.filter.size_range_label(&symbols);
+        if !size_range.is_empty() {
+            spans.push(Span::raw(" "));
+            spans.push(Span::styled(
+                format!("[{}]", size_range),
+                Style::default().fg(theme.accent),
+            ));
+        }
+        spans.push(Span::raw(format!(
+            " ({}/{})",
+            self.state.filtered_indices.len(),
+            self.state.sessions.len()
+        )));
+        let filter_line = Line::from(spans);
 
         f.render_widget(Paragraph::new(filter_line), area);
     }
 
+    /// Screen rows a preview line takes up (code blocks aren't wrapped)
+    fn preview_line_rows(&self, idx: usize, wrap_width: usize) -> usize {
+        let Some(line) = self.state.preview_lines.get(idx) else {
+            return 0;
+        };
+        let in_code = line.starts_with("```")
+            || self
+                .code_blocks
+                .iter()
+                .any(|block| idx >= block.start && idx < block.end);
+        if in_code || !self.state.preview_wrap {
+            1
+        } else {
+            wrap_line(line, wrap_width).len().max(1)
+        }
+    }
+
     fn draw_session_table(&mut self, f: &mut Frame, area: Rect) {
-        let header_cells = ["", "Project", "Date", "Size", "Tokens", "Preview"]
-            .iter()
-            .map(|h| Cell::from(*h).style(Style::default().add_modifier(Modifier::BOLD)));
+        let theme = self.state.color_theme.theme();
+        let symbols = self.state.render_options.symbols();
+        let columns = &self.state.columns;
+        let header_cells = ["", ""]
+            .into_iter()
+            .chain(columns.iter().map(|&c| self.column_title(c)))
+            .map(|h| Cell::from(h).style(Style::default().add_modifier(Modifier::BOLD)));
         let header = Row::new(header_cells).height(1);
+        let now = chrono::Utc::now();
 
         let rows: Vec<Row> = self
             .state
@@ -764,74 +2118,189 @@ impl App {
             .map(|(row_idx, &session_idx)| {
                 let session = &self.state.sessions[session_idx];
                 let selected = self.state.is_selected(session_idx);
+                let active = self.state.active_session == Some(session_idx);
 
-                let sel_marker = if selected { "●" } else { " " };
-                let project = &session.project;
-                let date = session.modified.format("%b %d").to_string();
-                let size = humansize::format_size(session.size_bytes, humansize::BINARY);
-                let tokens = session
-                    .token_count
-                    .map(format_tokens)
-                    .unwrap_or_else(|| "-".to_string());
-                let preview = get_session_preview(session);
+                let sel_marker = if selected { symbols.selected } else { " " };
+                let pin_marker = if active {
+                    symbols.active
+                } else if self.state.is_pinned(session_idx) {
+                    symbols.pinned
+                } else {
+                    " "
+                };
 
                 let style = if row_idx == self.state.cursor {
                     Style::default()
-                        .bg(Color::DarkGray)
+                        .bg(theme.cursor_bg)
                         .add_modifier(Modifier::BOLD)
                 } else if selected {
-                    Style::default().fg(Color::Yellow)
+                    Style::default().fg(theme.highlight)
+                } else if active {
+                    Style::default().fg(theme.positive)
+                } else if session.is_empty() {
+                    // Dim prune candidates
+                    Style::default().fg(theme.muted)
                 } else {
                     Style::default()
                 };
 
-                Row::new(vec![
-                    Cell::from(sel_marker),
-                    Cell::from(project.as_str()),
-                    Cell::from(date),
-                    Cell::from(size),
-                    Cell::from(tokens),
-                    Cell::from(preview),
-                ])
-                .style(style)
+                let cells = [Cell::from(sel_marker), Cell::from(pin_marker)]
+                    .into_iter()
+                    .chain(
+                        columns
+                            .iter()
+                            .map(|&c| self.column_cell(c, session, now)),
+                    );
+                Row::new(cells).style(style)
             })
             .collect();
 
-        let widths = [
-            Constraint::Length(2),
-            Constraint::Length(15),
-            Constraint::Length(8),
-            Constraint::Length(8),
-            Constraint::Length(8),
-            Constraint::Min(20),
-        ];
+        let widths: Vec<Constraint> = [Constraint::Length(2), Constraint::Length(1)]
+            .into_iter()
+            .chain(columns.iter().map(|&c| column_width(c)))
+            .collect();
 
         let table = Table::new(rows, widths)
             .header(header)
             .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
 
+        // Keep the rendered window in sync with the state's scroll position
+        *self.table_state.offset_mut() = self.state.scroll_offset;
+        self.table_area = area;
         f.render_stateful_widget(table, area, &mut self.table_state);
     }
 
+    /// Header for a table column (`#` swaps the preview for the session id)
+    fn column_title(&self, column: Column) -> &'static str {
+        match column {
+            Column::Preview if self.state.show_ids => Column::Id.title(),
+            c => c.title(),
+        }
+    }
+
+    /// Table cell for one session; the age is colored by how recent it is
+    fn column_cell(&self, column: Column, session: &Session, now: DateTime<Utc>) -> Cell<'static> {
+        let theme = self.state.color_theme.theme();
+        let cell = Cell::from(self.column_text(column, session, now));
+        if column != Column::Age {
+            return cell;
+        }
+        let days = now.signed_duration_since(session.modified).num_days();
+        cell.style(if days < 7 {
+            Style::default().fg(theme.positive)
+        } else if days < 30 {
+            Style::default().fg(theme.highlight)
+        } else {
+            Style::default().add_modifier(Modifier::DIM)
+        })
+    }
+
+    /// Cell text for one session in a table column
+    fn column_text(&self, column: Column, session: &Session, now: DateTime<Utc>) -> String {
+        let local = self.state.render_options.local_time;
+        match column {
+            Column::Project => session.project.clone(),
+            Column::Date => self
+                .state
+                .relative_time
+                .then(|| format_relative_time(session.modified, now))
+                .flatten()
+                .unwrap_or_else(|| format_time(session.modified, "%b %d", local)),
+            Column::Created => session
+                .created
+                .map(|created| format_time(created, "%b %d", local))
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Age => format_age(session.modified, now),
+            Column::Size => humansize::format_size(session.size_bytes, humansize::BINARY),
+            Column::Msgs => session
+                .message_count
+                .map(|n| n.to_string())
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Tokens => session
+                .token_count
+                .map(format_tokens)
+                .unwrap_or_else(|| "-".to_string()),
+            Column::Branch => session.git_branch.clone().unwrap_or_else(|| "-".to_string()),
+            Column::Id => session.id.clone(),
+            Column::Preview => {
+                let mut preview = if self.state.show_ids {
+                    session.id.clone()
+                } else {
+                    get_session_preview(session)
+                };
+                if session.has_code {
+                    preview = format!("</> {}", preview);
+                }
+                let symbols = self.state.render_options.symbols();
+                if session.has_directory {
+                    preview = format!("{} {}", symbols.sidecar, preview);
+                }
+                if session.is_agent {
+                    preview = format!("{} {}", symbols.agent, preview);
+                }
+                if session.parse_errors > 0 {
+                    preview = format!("{} {}", symbols.warning, preview);
+                }
+                let active = self.state.active_session.and_then(|i| self.state.sessions.get(i));
+                if active.is_some_and(|a| a.path == session.path) {
+                    preview = format!("[active] {}", preview);
+                }
+                preview
+            }
+        }
+    }
+
     fn draw_footer(&self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
         // Single line: status message OR keybinds hint
         let content = if let Some(ref msg) = self.state.status_message {
             Line::from(Span::styled(
                 msg.as_str(),
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.positive),
             ))
         } else if !self.state.selected.is_empty() {
             Line::from(Span::styled(
                 format!("{} selected", self.state.selected.len()),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.highlight),
             ))
         } else {
-            Line::from(Span::styled("?:help", Style::default().fg(Color::DarkGray)))
+            Line::from(Span::styled("?:help", Style::default().fg(theme.muted)))
         };
-        f.render_widget(Paragraph::new(content), area);
+
+        // Totals for the currently filtered sessions, right-aligned
+        let (count, bytes, tokens) = self.state.filtered_totals();
+        let symbols = self.state.render_options.symbols();
+        let mut summary = format!(
+            "{} sessions {sep} {} {sep} {} tokens",
+            count,
+            humansize::format_size(bytes, humansize::BINARY),
+            format_tokens(tokens),
+            sep = symbols.separator
+        );
+        if let Some(price) = self.state.price_per_million_tokens {
+            summary.push_str(&format!(
+                " {} {}${:.2}",
+                symbols.separator,
+                symbols.approx,
+                tokens as f64 / 1_000_000.0 * price
+            ));
+        }
+        let summary_width = unicode_width::UnicodeWidthStr::width(summary.as_str()) as u16;
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Length(summary_width)])
+            .split(area);
+
+        f.render_widget(Paragraph::new(content), chunks[0]);
+        f.render_widget(
+            Paragraph::new(Span::styled(summary, Style::default().fg(theme.muted))),
+            chunks[1],
+        );
     }
 
     fn draw_preview_view(&mut self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let symbols = self.state.render_options.symbols();
         let has_search = !self.state.preview_search.is_empty() || self.state.preview_search_active;
         let constraints = if has_search {
             vec![
@@ -851,7 +2320,7 @@ impl App {
         let (content_area, footer_area) = if has_search {
             // Draw search bar
             let search_text = if self.state.preview_search_active {
-                format!("Search: [{}▏]", self.state.preview_search)
+                format!("Search: [{}{}]", self.state.preview_search, symbols.caret)
             } else {
                 let match_info = if !self.state.preview_matches.is_empty() {
                     format!(
@@ -866,7 +2335,8 @@ impl App {
                 };
                 format!("Search: [{}]{}", self.state.preview_search, match_info)
             };
-            let search_bar = Paragraph::new(search_text).style(Style::default().fg(Color::Yellow));
+            let search_bar =
+                Paragraph::new(search_text).style(Style::default().fg(theme.highlight));
             f.render_widget(search_bar, chunks[0]);
             (chunks[1], chunks[2])
         } else {
@@ -875,14 +2345,75 @@ impl App {
 
         // Get session info for title
         let title = if let Some(session) = self.state.current_session() {
-            format!(" Preview: {} - {} ", session.project, session.id)
+            let mut title = format!(" Preview: {} - {} ", session.project, session.id);
+            if session.message_count.is_some() {
+                title.push_str(&format!(
+                    "({} user / {} assistant, {} tool calls) ",
+                    session.user_message_count,
+                    session.assistant_message_count,
+                    session.tool_use_count
+                ));
+            }
+            if let (Some(duration), Some(gap)) = (session.duration, session.longest_gap) {
+                title.push_str(&format!(
+                    "({} long, longest gap {}) ",
+                    format_duration(duration),
+                    format_duration(gap)
+                ));
+            }
+            if session.has_directory {
+                title.push_str(&format!(
+                    "({} {} file(s), {}) ",
+                    symbols.sidecar,
+                    session.dir_file_count,
+                    humansize::format_size(session.dir_size_bytes, humansize::BINARY)
+                ));
+            }
+            if session.parse_errors > 0 {
+                title.push_str(&format!(
+                    "({} {} unparseable line(s)) ",
+                    symbols.warning,
+                    session.parse_errors
+                ));
+            }
+            title
         } else {
             " Preview ".to_string()
         };
+        let title = if self.state.preview_wrap {
+            title
+        } else {
+            format!("{}[no wrap, col {}] ", title, self.state.preview_hscroll)
+        };
+
+        self.preview_height = (content_area.height.saturating_sub(2) as usize).max(1);
+        let wrap_width = content_area.width.saturating_sub(2) as usize; // Account for borders
+
+        // Scroll just enough to keep the cursor line fully visible
+        let cursor = self.state.preview_cursor;
+        if cursor < self.state.preview_scroll {
+            self.state.preview_scroll = cursor;
+        }
+        let mut rows: usize = (self.state.preview_scroll..=cursor)
+            .map(|idx| self.preview_line_rows(idx, wrap_width))
+            .sum();
+        while rows > self.preview_height && self.state.preview_scroll < cursor {
+            rows -= self.preview_line_rows(self.state.preview_scroll, wrap_width);
+            self.state.preview_scroll += 1;
+        }
 
         // Pre-compute which lines are in code blocks
         let code_blocks = &self.code_blocks;
-        let wrap_width = content_area.width.saturating_sub(2) as usize; // Account for borders
+        let no_wrap = !self.state.preview_wrap;
+        let markdown = self.state.preview_markdown;
+        let hscroll = self.state.preview_hscroll;
+        let wrap = |text: &str| {
+            if no_wrap {
+                vec![Line::from(text.to_string())]
+            } else {
+                wrap_line(text, wrap_width)
+            }
+        };
 
         let items: Vec<ListItem> = self
             .state
@@ -892,8 +2423,14 @@ impl App {
             .skip(self.state.preview_scroll)
             .take(content_area.height as usize)
             .map(|(idx, line)| {
-                let is_match = self.state.preview_matches.contains(&idx);
+                let match_ranges = self.state.preview_match_ranges.get(&idx);
+                let is_current_match = self
+                    .state
+                    .preview_matches
+                    .get(self.state.preview_match_index)
+                    == Some(&idx);
                 let is_selected = self.state.is_line_in_selected_section(idx);
+                let is_cursor = idx == self.state.preview_cursor;
 
                 // Check if this line is in a code block
                 let in_code_block = code_blocks
@@ -909,23 +2446,23 @@ impl App {
                 // Determine styling
                 let (content, base_style) = if line.starts_with("[User]") {
                     (
-                        wrap_line(line, wrap_width),
+                        wrap(line),
                         Style::default()
-                            .fg(Color::Green)
+                            .fg(theme.user)
                             .add_modifier(Modifier::BOLD),
                     )
                 } else if line.starts_with("[Assistant]") {
                     (
-                        wrap_line(line, wrap_width),
+                        wrap(line),
                         Style::default()
-                            .fg(Color::Blue)
+                            .fg(theme.assistant)
                             .add_modifier(Modifier::BOLD),
                     )
                 } else if line.starts_with("[System]") {
                     (
-                        wrap_line(line, wrap_width),
+                        wrap(line),
                         Style::default()
-                            .fg(Color::Yellow)
+                            .fg(theme.system)
                             .add_modifier(Modifier::BOLD),
                     )
                 } else if is_code_fence {
@@ -933,11 +2470,11 @@ impl App {
                     let lang_display = code_block_lang.unwrap_or("");
                     (
                         vec![Line::from(vec![
-                            Span::styled("```", Style::default().fg(Color::Magenta)),
+                            Span::styled("```", Style::default().fg(theme.sort)),
                             Span::styled(
                                 lang_display.to_string(),
                                 Style::default()
-                                    .fg(Color::Cyan)
+                                    .fg(theme.accent)
                                     .add_modifier(Modifier::ITALIC),
                             ),
                         ])],
@@ -951,49 +2488,79 @@ impl App {
                         if let Some(first_line) = highlighted.into_iter().next() {
                             (
                                 vec![first_line],
-                                Style::default().bg(Color::Rgb(30, 30, 46)),
+                                Style::default().bg(theme.code_bg),
                             )
                         } else {
                             (
                                 vec![Line::from(line.as_str())],
-                                Style::default().bg(Color::Rgb(30, 30, 46)),
+                                Style::default().bg(theme.code_bg),
                             )
                         }
                     } else {
                         (
                             vec![Line::from(line.as_str())],
-                            Style::default().bg(Color::Rgb(30, 30, 46)),
+                            Style::default().bg(theme.code_bg),
                         )
                     }
-                } else if line.starts_with("🔧") {
+                } else if line.starts_with(symbols.tool) {
                     // Tool use - wrap
                     (
-                        wrap_line(line, wrap_width),
-                        Style::default().fg(Color::Cyan),
+                        wrap(line),
+                        Style::default().fg(theme.accent),
                     )
-                } else if line.starts_with("💭") {
+                } else if line.starts_with(symbols.thinking) {
                     // Thinking - wrap
                     (
-                        wrap_line(line, wrap_width),
+                        wrap(line),
                         Style::default()
-                            .fg(Color::DarkGray)
+                            .fg(theme.muted)
                             .add_modifier(Modifier::ITALIC),
                     )
-                } else if line.starts_with("📋") {
+                } else if line.starts_with(symbols.result) {
                     // Tool result - wrap
                     (
-                        wrap_line(line, wrap_width),
-                        Style::default().fg(Color::Gray),
+                        wrap(line),
+                        Style::default().fg(theme.tool_result),
                     )
+                } else if markdown {
+                    let content = markdown_styles(line, &theme)
+                        .into_iter()
+                        .fold(wrap(line), |content, (range, style)| {
+                            highlight_ranges(content, std::slice::from_ref(&range), style)
+                        });
+                    (content, Style::default())
+                } else {
+                    (wrap(line), Style::default())
+                };
+
+                // Highlight the matched text itself, brighter on the current match
+                let content = match match_ranges {
+                    Some(ranges) => {
+                        let match_style = if is_current_match {
+                            Style::default().bg(theme.highlight).fg(theme.popup_bg)
+                        } else {
+                            Style::default().bg(theme.cursor_bg).fg(theme.highlight)
+                        };
+                        highlight_ranges(content, ranges, match_style)
+                    }
+                    None => content,
+                };
+
+                // In no-wrap mode, shift everything left by the horizontal scroll
+                let content = if no_wrap && hscroll > 0 {
+                    content
+                        .into_iter()
+                        .map(|line| skip_columns(line, hscroll))
+                        .collect()
                 } else {
-                    (wrap_line(line, wrap_width), Style::default())
+                    content
                 };
 
-                // Highlight matched lines and selected sections
-                let final_style = if is_match {
-                    base_style.bg(Color::DarkGray)
+                // Highlight the cursor line and selected sections
+                let final_style = if is_cursor {
+                    base_style.bg(theme.line_cursor_bg)
                 } else if is_selected {
-                    base_style.bg(Color::Rgb(50, 50, 80))
+                    base_style.bg(theme.line_selected_bg)
                 } else {
                     base_style
                 };
@@ -1010,115 +2577,442 @@ impl App {
         let footer = if let Some(ref msg) = self.state.status_message {
             Line::from(Span::styled(
                 msg.as_str(),
-                Style::default().fg(Color::Green),
+                Style::default().fg(theme.positive),
             ))
         } else if !self.state.preview_selected_sections.is_empty() {
             Line::from(vec![
                 Span::styled(
                     format!("{} selected ", self.state.preview_selected_sections.len()),
-                    Style::default().fg(Color::Yellow),
+                    Style::default().fg(theme.highlight),
                 ),
-                Span::styled("Space", Style::default().fg(Color::Cyan)),
+                Span::styled("Space", Style::default().fg(theme.accent)),
                 Span::raw(":Toggle "),
-                Span::styled("c", Style::default().fg(Color::Cyan)),
+                Span::styled("c", Style::default().fg(theme.accent)),
                 Span::raw(":Copy "),
-                Span::styled("A", Style::default().fg(Color::Cyan)),
+                Span::styled("A", Style::default().fg(theme.accent)),
                 Span::raw(":Clear"),
             ])
         } else {
             Line::from(vec![
-                Span::styled("j/k", Style::default().fg(Color::Cyan)),
-                Span::raw(":Scroll "),
-                Span::styled("[/]", Style::default().fg(Color::Cyan)),
+                Span::styled("j/k", Style::default().fg(theme.accent)),
+                Span::raw(":Cursor "),
+                Span::styled("[/]", Style::default().fg(theme.accent)),
                 Span::raw(":Section "),
-                Span::styled("Space", Style::default().fg(Color::Cyan)),
+                Span::styled("Space", Style::default().fg(theme.accent)),
                 Span::raw(":Select "),
-                Span::styled("c", Style::default().fg(Color::Cyan)),
+                Span::styled("y", Style::default().fg(theme.accent)),
+                Span::raw(":Copy msg "),
+                Span::styled("c", Style::default().fg(theme.accent)),
                 Span::raw(":Copy "),
-                Span::styled("q", Style::default().fg(Color::Cyan)),
+                Span::styled("e", Style::default().fg(theme.accent)),
+                Span::raw(":Copy MD "),
+                Span::styled("q", Style::default().fg(theme.accent)),
                 Span::raw(":Back"),
             ])
         };
         f.render_widget(Paragraph::new(footer), footer_area);
     }
 
-    fn draw_help_overlay(&self, f: &mut Frame, area: Rect) {
-        let help_text = vec![
+    fn draw_diff_view(&self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(self.state.diff_header.len() as u16),
+                Constraint::Min(3),
+                Constraint::Length(1),
+            ])
+            .split(area);
+
+        let header: Vec<Line> = self
+            .state
+            .diff_header
+            .iter()
+            .enumerate()
+            .map(|(i, text)| {
+                let color = match i {
+                    0 => theme.negative,
+                    1 => theme.positive,
+                    _ => theme.accent,
+                };
+                Line::from(Span::styled(text.as_str(), Style::default().fg(color)))
+            })
+            .collect();
+        f.render_widget(Paragraph::new(header), chunks[0]);
+
+        let lines: Vec<Line> = self
+            .state
+            .diff_ops
+            .iter()
+            .skip(self.state.diff_scroll)
+            .map(|op| match op {
+                DiffOp::Same(text) => Line::from(Span::raw(format!("  {}", text))),
+                DiffOp::Left(text) => Line::from(Span::styled(
+                    format!("- {}", text),
+                    Style::default().fg(theme.negative),
+                )),
+                DiffOp::Right(text) => Line::from(Span::styled(
+                    format!("+ {}", text),
+                    Style::default().fg(theme.positive),
+                )),
+            })
+            .collect();
+        let body = Paragraph::new(lines).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Compare user messages "),
+        );
+        f.render_widget(body, chunks[1]);
+
+        let footer = Line::from(vec![
+            Span::styled("j/k", Style::default().fg(theme.highlight)),
+            Span::raw(":Scroll "),
+            Span::styled("q", Style::default().fg(theme.highlight)),
+            Span::raw(":Back"),
+        ]);
+        f.render_widget(Paragraph::new(footer), chunks[2]);
+    }
+
+    fn draw_help_overlay(&mut self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let (title, help_text) = match self.state.help_context {
+            View::Preview => (" Help: Preview ", PREVIEW_HELP.to_vec()),
+            View::Search => (" Help: Search ", SEARCH_HELP.to_vec()),
+            View::Diff => (" Help: Compare ", DIFF_HELP.to_vec()),
+            _ => (" Help ", Vec::new()),
+        };
+        let delete_older = format!(
+            "  D               Delete older than {} days",
+            self.state.older_than_days
+        );
+        let list_help = vec![
             "",
             "  Navigation",
             "  j/k, Up/Down    Move cursor",
             "  g/G, Home/End   Go to top/bottom",
             "  PgUp/PgDn       Page up/down",
-            "  Enter           Open preview",
+            "  Enter           Open preview (at first search match)",
             "",
             "  Selection",
             "  Space           Toggle selection",
             "  v               Visual select mode",
             "  a               Select all",
             "  A               Clear selection",
+            "  I               Invert selection",
+            "  E               Select empty sessions (dimmed)",
+            "  C               Compare 2 selected sessions",
             "",
             "  Filters & Sort",
-            "  /               Search",
+            "  /               Search (Tab: whole word)",
             "  p               Cycle project filter",
-            "  s               Cycle sort (date/size/project/name)",
+            "  P               Pick project (type to filter)",
+            "  M               Show only pinned",
+            "  w               Cycle date range",
+            "  S               Cycle minimum size (100K, 1M, 10M)",
+            "  F               Keep the active (newest) session on top",
+            "  f               Only recently active projects",
+            "  i               Show/hide agent sessions",
+            "  H               Show/hide system-only sessions",
+            "  b               Only sessions with code blocks (</>)",
+            "  s               Cycle sort (date/size/project/name/created)",
             "  o               Toggle sort order",
+            "  t               Toggle relative dates",
+            "  T               Cycle color theme",
+            "  U               Toggle local time / UTC",
+            "  #               Toggle preview / session id column",
+            "",
+            "  Pins",
+            "  m               Pin/unpin session",
             "",
             "  Clipboard",
             "  y               Copy resume command",
+            "  B               Copy resume commands of all selected",
             "  Y               Copy session path",
+            "  Ctrl+y          Copy session id",
             "",
             "  Actions",
             "  d               Delete selected",
-            "  D               Delete older than 30 days",
-            "  e               Export to Markdown",
-            "  z               Archive to tar.gz",
+            delete_older.as_str(),
+            "  e               Export to Markdown (confirm)",
+            "  J               Merge selected resume chain into one export",
+            "  z               Archive to tar.gz (confirm)",
+            "  Z               Archive selection as one named tar.gz",
+            "  u               Undo last export/archive (confirm)",
             "  r               Refresh list",
+            "  Ctrl+r          Rebuild search index (reparse all)",
+            "  O               Open project dir ($EDITOR)",
+            "  L               Open last export/archive folder",
+            "  X               Open raw JSONL ($PAGER)",
+            "  R               Resume in claude (new terminal)",
             "",
-            "  ?               Toggle help",
+            "  ?, F1           Toggle help",
             "  q               Quit",
             "",
         ];
-
-        let help_height = help_text.len() as u16 + 2;
-        let help_width = 45;
+        let help_text = if help_text.is_empty() { list_help } else { help_text };
+
+        // Wide enough for the longest entry (plus borders and a margin) and scrollable
+        // when taller than the terminal
+        let longest = help_text.iter().map(|line| line.chars().count()).max().unwrap_or(0);
+        let help_width = (longest as u16 + 4).min(area.width);
+        let help_height = (help_text.len() as u16 + 2).min(area.height);
+        let page = help_height.saturating_sub(2) as usize;
+        let max_scroll = help_text.len().saturating_sub(page);
+        self.state.help_scroll = self.state.help_scroll.min(max_scroll);
+        self.state.help_page = page;
+        let title = if max_scroll > 0 {
+            format!("{}(j/k to scroll) ", title)
+        } else {
+            title.to_string()
+        };
 
         let popup_area = centered_rect(help_width, help_height, area);
 
-        let help_items: Vec<ListItem> = help_text.iter().map(|line| ListItem::new(*line)).collect();
+        let help_items: Vec<ListItem> = help_text
+            .iter()
+            .skip(self.state.help_scroll)
+            .map(|line| ListItem::new(*line))
+            .collect();
 
         let help = List::new(help_items)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
-                    .title(" Help ")
-                    .style(Style::default().bg(Color::Black)),
+                    .title(title)
+                    .style(Style::default().bg(theme.popup_bg)),
             )
-            .style(Style::default().bg(Color::Black));
+            .style(Style::default().bg(theme.popup_bg));
 
         f.render_widget(Clear, popup_area);
         f.render_widget(help, popup_area);
     }
 
+    fn draw_project_picker(&self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let entries = self.state.project_picker_entries();
+        let popup_area = centered_rect(50, area.height.saturating_sub(4).min(20), area);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(1)])
+            .split(popup_area);
+
+        let input = Paragraph::new(format!("{}_", self.state.picker_query)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(" Project (type to filter) ")
+                .style(Style::default().bg(theme.popup_bg)),
+        );
+
+        let items: Vec<ListItem> = entries
+            .iter()
+            .map(|(name, count)| {
+                let label = name.unwrap_or("All");
+                ListItem::new(Line::from(vec![
+                    Span::raw(format!("{:<38}", label.chars().take(38).collect::<String>())),
+                    Span::styled(format!("{:>6}", count), Style::default().fg(theme.muted)),
+                ]))
+            })
+            .collect();
+
+        let mut list_state = ratatui::widgets::ListState::default();
+        if !entries.is_empty() {
+            list_state.select(Some(self.state.picker_cursor));
+        }
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .style(Style::default().bg(theme.popup_bg)),
+            )
+            .highlight_style(Style::default().bg(theme.cursor_bg).add_modifier(Modifier::BOLD));
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(input, chunks[0]);
+        f.render_stateful_widget(list, chunks[1], &mut list_state);
+    }
+
+    /// "Loading preview" popup while a slow preview load is running
+    fn draw_preview_spinner(&self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let Some(ref load) = self.preview_load else {
+            return;
+        };
+        let spinner = self.state.render_options.symbols().spinner;
+        let frame = (load.started.elapsed().as_millis() / 100) as usize % spinner.len();
+        let popup_area = centered_rect(44, 3, area);
+        let text = Paragraph::new(format!(
+            " {} Loading preview... (Esc to cancel)",
+            spinner[frame]
+        ))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .style(Style::default().bg(theme.popup_bg)),
+        );
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(text, popup_area);
+    }
+
+    fn draw_archive_name_prompt(&self, f: &mut Frame, area: Rect) {
+        let theme = self.state.color_theme.theme();
+        let count = self.state.action_targets().len();
+        let popup_area = centered_rect(60, 3, area);
+        let input = Paragraph::new(format!("{}_", self.state.archive_name)).block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title(format!(" Archive {} session(s) as (.tar.gz) ", count))
+                .style(Style::default().bg(theme.popup_bg)),
+        );
+
+        f.render_widget(Clear, popup_area);
+        f.render_widget(input, popup_area);
+    }
+
     fn draw_confirm_dialog(&self, f: &mut Frame, area: Rect) {
-        let msg = self.state.dialog_message.as_deref().unwrap_or("Confirm?");
+        let theme = self.state.color_theme.theme();
+        const MAX_TARGETS: usize = 10;
 
-        let popup_area = centered_rect(50, 5, area);
+        let mut msg = self
+            .state
+            .dialog_message
+            .as_deref()
+            .unwrap_or("Confirm?")
+            .to_string();
+        let targets = &self.state.dialog_targets;
+        for session in targets
+            .iter()
+            .take(MAX_TARGETS)
+            .filter_map(|&idx| self.state.sessions.get(idx))
+        {
+            msg.push_str(&format!(
+                "\n  {} / {} ({})",
+                session.project,
+                session.id,
+                humansize::format_size(session.size_bytes, humansize::BINARY)
+            ));
+            if session.has_directory {
+                msg.push_str(&format!(
+                    " + {} {}",
+                    self.state.render_options.symbols().sidecar,
+                    humansize::format_size(session.dir_size_bytes, humansize::BINARY)
+                ));
+            }
+        }
+        if targets.len() > MAX_TARGETS {
+            msg.push_str(&format!("\n  ...and {} more", targets.len() - MAX_TARGETS));
+        }
+        let msg = msg.as_str();
+
+        // Grow to fit multi-line messages (e.g. target file lists)
+        let longest = msg
+            .lines()
+            .map(unicode_width::UnicodeWidthStr::width)
+            .max()
+            .unwrap_or(0) as u16;
+        let width = (longest + 4).max(50).min(area.width);
+        let height = (msg.lines().count() as u16 + 2).max(5);
+        let popup_area = centered_rect(width, height, area);
 
         let dialog = Paragraph::new(msg)
             .block(
                 Block::default()
                     .borders(Borders::ALL)
                     .title(" Confirm ")
-                    .style(Style::default().bg(Color::Black)),
+                    .style(Style::default().bg(theme.popup_bg)),
             )
-            .style(Style::default().bg(Color::Black))
-            .wrap(Wrap { trim: true });
+            .style(Style::default().bg(theme.popup_bg))
+            .wrap(Wrap { trim: false });
 
         f.render_widget(Clear, popup_area);
         f.render_widget(dialog, popup_area);
     }
 }
 
+/// Run a command in the terminal with the TUI suspended, restoring it afterwards
+///
+/// The outer result is terminal setup; the inner one is whether the command could run.
+fn run_suspended(
+    terminal: &mut ratatui::Terminal<impl Backend>,
+    command: &mut Command,
+) -> Result<io::Result<()>> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture)?;
+    let status = command.status();
+    enable_raw_mode()?;
+    execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+    terminal.clear()?;
+    Ok(status.map(|_| ()))
+}
+
+/// Note for confirmations when targets have sidecar directories, e.g. "(included)"
+fn sidecar_note(sessions: &[&Session], handling: &str, symbols: &Symbols) -> Option<String> {
+    let (count, bytes) = sessions
+        .iter()
+        .filter(|s| s.has_directory)
+        .fold((0, 0), |(count, bytes), s| (count + 1, bytes + s.dir_size_bytes));
+    (count > 0).then(|| {
+        format!(
+            "{} {} session(s) have sidecar directories ({}, {})",
+            symbols.sidecar,
+            count,
+            humansize::format_size(bytes, humansize::BINARY),
+            handling
+        )
+    })
+}
+
+/// Build a confirmation message listing the first few target file names
+fn format_target_list(
+    verb: &str,
+    names: &[String],
+    dir: &std::path::Path,
+    note: Option<&str>,
+) -> String {
+    const MAX_SHOWN: usize = 5;
+
+    let mut lines = vec![format!(
+        "{} {} session(s) to {}?",
+        verb,
+        names.len(),
+        dir.display()
+    )];
+    lines.extend(names.iter().take(MAX_SHOWN).map(|name| format!("  {}", name)));
+    if names.len() > MAX_SHOWN {
+        lines.push(format!("  ...and {} more", names.len() - MAX_SHOWN));
+    }
+    lines.extend(note.map(str::to_string));
+    lines.push("(y/n)".to_string());
+    lines.join("\n")
+}
+
+/// Table width for a column; the preview takes whatever is left
+fn column_width(column: Column) -> Constraint {
+    match column {
+        Column::Project => Constraint::Length(15),
+        Column::Date | Column::Created | Column::Size | Column::Tokens => Constraint::Length(8),
+        Column::Msgs | Column::Age => Constraint::Length(5),
+        Column::Branch => Constraint::Length(16),
+        Column::Id => Constraint::Length(36),
+        Column::Preview => Constraint::Min(20),
+    }
+}
+
+/// Draw a centered progress gauge labelled "done/total"
+fn draw_progress(f: &mut Frame, theme: Theme, title: &str, done: usize, total: usize) {
+    let area = f.size();
+    let gauge_area = centered_rect(area.width.saturating_sub(4).min(60), 3, area);
+    let ratio = if total == 0 { 1.0 } else { done as f64 / total as f64 };
+
+    let gauge = Gauge::default()
+        .block(Block::default().borders(Borders::ALL).title(format!(" {} ", title)))
+        .gauge_style(Style::default().fg(theme.accent))
+        .ratio(ratio.clamp(0.0, 1.0))
+        .label(format!("{}/{}", done, total));
+    f.render_widget(gauge, gauge_area);
+}
+
 /// Helper function to create a centered rect
 fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     let x = area.x + (area.width.saturating_sub(width)) / 2;
@@ -1126,6 +3020,88 @@ fn centered_rect(width: u16, height: u16, area: Rect) -> Rect {
     Rect::new(x, y, width.min(area.width), height.min(area.height))
 }
 
+/// Restyle the byte `ranges` of a rendered line, which may span several wrapped `Line`s
+/// whose text concatenates back to the original
+fn highlight_ranges<'a>(
+    lines: Vec<Line<'a>>,
+    ranges: &[Range<usize>],
+    style: Style,
+) -> Vec<Line<'a>> {
+    let mut offset = 0;
+
+    lines
+        .into_iter()
+        .map(|line| {
+            let alignment = line.alignment;
+            let mut spans = Vec::new();
+
+            for span in line.spans {
+                let text = span.content.as_ref();
+                let span_start = offset;
+                let span_end = offset + text.len();
+                offset = span_end;
+
+                // Split points inside this span, clamped to char boundaries
+                let mut cuts = vec![0, text.len()];
+                for range in ranges {
+                    for point in [range.start, range.end] {
+                        if point > span_start && point < span_end {
+                            cuts.push(point - span_start);
+                        }
+                    }
+                }
+                cuts.retain(|&c| text.is_char_boundary(c));
+                cuts.sort_unstable();
+                cuts.dedup();
+
+                for pair in cuts.windows(2) {
+                    let (from, to) = (pair[0], pair[1]);
+                    let abs = span_start + from;
+                    let piece_style = if ranges.iter().any(|r| r.start <= abs && abs < r.end) {
+                        span.style.patch(style)
+                    } else {
+                        span.style
+                    };
+                    spans.push(Span::styled(text[from..to].to_string(), piece_style));
+                }
+            }
+
+            let mut line = Line::from(spans);
+            line.alignment = alignment;
+            line
+        })
+        .collect()
+}
+
+/// Drop the first `cols` display columns of a line, keeping span styles
+fn skip_columns(line: Line<'_>, cols: usize) -> Line<'_> {
+    let mut remaining = cols;
+    let mut spans = Vec::new();
+
+    for span in line.spans {
+        if remaining == 0 {
+            spans.push(span);
+            continue;
+        }
+        let mut cut = span.content.len();
+        for (idx, c) in span.content.char_indices() {
+            if remaining == 0 {
+                cut = idx;
+                break;
+            }
+            let width = unicode_width::UnicodeWidthChar::width(c).unwrap_or(0);
+            remaining = remaining.saturating_sub(width.max(1));
+        }
+        if cut < span.content.len() {
+            spans.push(Span::styled(span.content[cut..].to_string(), span.style));
+        }
+    }
+
+    let mut shifted = Line::from(spans);
+    shifted.alignment = line.alignment;
+    shifted
+}
+
 /// Wrap a line of text to fit within the given width
 fn wrap_line(text: &str, max_width: usize) -> Vec<Line<'static>> {
     if max_width == 0 {
@@ -1193,3 +3169,47 @@ fn wrap_line(text: &str, max_width: usize) -> Vec<Line<'static>> {
 
     lines
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::backend::TestBackend;
+
+    #[test]
+    fn test_confirm_dialog_fits_narrow_terminal() {
+        let mut app = App::new(UiState::new(Vec::new()), Highlighter::new());
+        app.state.dialog_message = Some("Delete 1 session?".to_string());
+
+        let mut terminal = ratatui::Terminal::new(TestBackend::new(45, 12)).unwrap();
+        terminal.draw(|f| app.draw_confirm_dialog(f, f.size())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let text: String = buffer.content().iter().map(|c| c.symbol()).collect();
+        assert!(text.contains("Delete 1 session?"));
+    }
+
+    #[test]
+    fn test_help_overlay_shows_full_entries_and_scrolls() {
+        let mut app = App::new(UiState::new(Vec::new()), Highlighter::new());
+        app.state.open_help();
+        let mut terminal = ratatui::Terminal::new(TestBackend::new(80, 20)).unwrap();
+        let screen = |terminal: &ratatui::Terminal<TestBackend>| -> String {
+            let buffer = terminal.backend().buffer();
+            buffer.content().iter().map(|c| c.symbol()).collect()
+        };
+
+        terminal.draw(|f| app.draw_help_overlay(f, f.size())).unwrap();
+        assert!(screen(&terminal).contains("Open preview (at first search match)"));
+        assert!(!screen(&terminal).contains("Quit"));
+
+        app.handle_help_keys(KeyCode::Char('G'));
+        terminal.draw(|f| app.draw_help_overlay(f, f.size())).unwrap();
+        assert!(screen(&terminal).contains("Merge selected resume chain into one export"));
+        assert!(screen(&terminal).contains("Quit"));
+
+        // Scrolling back up starts from the clamped end, not from usize::MAX
+        let end = app.state.help_scroll;
+        app.handle_help_keys(KeyCode::Char('k'));
+        assert_eq!(app.state.help_scroll, end - 1);
+    }
+}
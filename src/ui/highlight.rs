@@ -1,14 +1,20 @@
+use super::theme::Theme;
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
+use std::ops::Range;
 use syntect::easy::HighlightLines;
 use syntect::highlighting::{Style as SyntectStyle, ThemeSet};
 use syntect::parsing::SyntaxSet;
 use syntect::util::LinesWithEndings;
 
+/// Theme used when none is requested or the requested one is unknown
+pub const DEFAULT_THEME: &str = "base16-ocean.dark";
+
 /// Syntax highlighter using syntect
 pub struct Highlighter {
     syntax_set: SyntaxSet,
     theme_set: ThemeSet,
+    theme_name: String,
 }
 
 impl Default for Highlighter {
@@ -22,7 +28,27 @@ impl Highlighter {
         Self {
             syntax_set: SyntaxSet::load_defaults_newlines(),
             theme_set: ThemeSet::load_defaults(),
+            theme_name: DEFAULT_THEME.to_string(),
+        }
+    }
+
+    /// Create a highlighter with the named theme, falling back to the default if unknown
+    pub fn with_theme(name: &str) -> Self {
+        let mut highlighter = Self::new();
+        if highlighter.theme_set.themes.contains_key(name) {
+            highlighter.theme_name = name.to_string();
         }
+        highlighter
+    }
+
+    /// Name of the theme in use
+    pub fn theme_name(&self) -> &str {
+        &self.theme_name
+    }
+
+    /// List the names of all bundled themes
+    pub fn available_themes(&self) -> Vec<&str> {
+        self.theme_set.themes.keys().map(|k| k.as_str()).collect()
     }
 
     /// Highlight a code block with the given language
@@ -33,7 +59,7 @@ impl Highlighter {
             .or_else(|| self.syntax_set.find_syntax_by_extension(lang))
             .unwrap_or_else(|| self.syntax_set.find_syntax_plain_text());
 
-        let theme = &self.theme_set.themes["base16-ocean.dark"];
+        let theme = &self.theme_set.themes[&self.theme_name];
         let mut highlighter = HighlightLines::new(syntax, theme);
 
         let mut result = Vec::new();
@@ -58,12 +84,6 @@ impl Highlighter {
 
         result
     }
-
-    /// Check if a language is supported
-    pub fn supports_language(&self, lang: &str) -> bool {
-        self.syntax_set.find_syntax_by_token(lang).is_some()
-            || self.syntax_set.find_syntax_by_extension(lang).is_some()
-    }
 }
 
 /// Convert syntect style to ratatui style
@@ -100,11 +120,18 @@ pub fn parse_code_blocks(lines: &[String]) -> Vec<CodeBlockInfo> {
     for (idx, line) in lines.iter().enumerate() {
         if line.starts_with("```") {
             if in_code_block {
-                // End of code block
+                // End of code block; guess the language for bare fences
+                let language = if current_lang.is_empty() {
+                    guess_language(&lines[block_start..idx])
+                        .unwrap_or_default()
+                        .to_string()
+                } else {
+                    current_lang.clone()
+                };
                 result.push(CodeBlockInfo {
                     start: block_start,
                     end: idx,
-                    language: current_lang.clone(),
+                    language,
                 });
                 in_code_block = false;
                 current_lang.clear();
@@ -120,6 +147,148 @@ pub fn parse_code_blocks(lines: &[String]) -> Vec<CodeBlockInfo> {
     result
 }
 
+/// Guess the language of an untagged code block from its body
+/// Conservative: returns None unless there is a strong marker or several weak ones
+pub fn guess_language(lines: &[String]) -> Option<&'static str> {
+    let first = lines.iter().map(|l| l.trim()).find(|l| !l.is_empty())?;
+
+    // Strong markers
+    if let Some(shebang) = first.strip_prefix("#!") {
+        return if shebang.contains("python") {
+            Some("python")
+        } else if shebang.contains("node") {
+            Some("js")
+        } else if shebang.contains("sh") {
+            Some("bash")
+        } else {
+            None
+        };
+    }
+    if lines.iter().any(|l| l.trim_start().starts_with("#include")) {
+        return Some("cpp");
+    }
+    if first.starts_with('{') || first.starts_with('[') {
+        let body = lines.join("\n");
+        if serde_json::from_str::<serde::de::IgnoredAny>(&body).is_ok() {
+            return Some("json");
+        }
+    }
+
+    // Weak markers: (language, line prefixes/fragments that suggest it)
+    const MARKERS: &[(&str, &[&str])] = &[
+        ("rust", &["fn ", "pub fn ", "let mut ", "impl ", "use std::", "#[derive", "-> "]),
+        ("python", &["def ", "import ", "from ", "class ", "self.", "elif "]),
+        ("go", &["package ", "func ", ":= ", "import ("]),
+        ("js", &["const ", "function ", "=> ", "console.log", "export ", "require("]),
+        ("bash", &["$ ", "cd ", "sudo ", "npm ", "cargo ", "git ", "echo ", "export "]),
+    ];
+
+    let mut best: Option<(&'static str, usize)> = None;
+    let mut tied = false;
+    for (lang, markers) in MARKERS {
+        let score = lines
+            .iter()
+            .filter(|line| {
+                let line = line.trim_start();
+                markers
+                    .iter()
+                    .any(|m| line.starts_with(m) || (m.len() > 3 && line.contains(m)))
+            })
+            .count();
+        match best {
+            Some((_, best_score)) if score == best_score => tied = true,
+            Some((_, best_score)) if score < best_score => {}
+            _ => {
+                best = Some((lang, score));
+                tied = false;
+            }
+        }
+    }
+
+    match best {
+        Some((lang, score)) if score >= 2 && !tied => Some(lang),
+        _ => None,
+    }
+}
+
+/// Markdown styling for one preview line as (byte range, style) pairs
+///
+/// Handles headers, list bullets, block quotes, `**bold**`, `*italic*` and `inline code`.
+/// The markup itself stays in place, dimmed, so search matches and wrapping still line
+/// up with the raw text. Later pairs are patched over earlier ones.
+pub fn markdown_styles(line: &str, theme: &Theme) -> Vec<(Range<usize>, Style)> {
+    let marker = Style::default().fg(theme.muted);
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+    let mut styles = Vec::new();
+    let mut body = indent;
+
+    let hashes = trimmed.bytes().take_while(|&b| b == b'#').count();
+    if (1..=6).contains(&hashes) && trimmed[hashes..].starts_with(' ') {
+        let color = if hashes == 1 { theme.sort } else { theme.accent };
+        styles.push((
+            indent..line.len(),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        ));
+        styles.push((indent..indent + hashes, marker));
+        body += hashes;
+    } else if let Some(len) = list_marker_len(trimmed) {
+        styles.push((indent..indent + len, Style::default().fg(theme.highlight)));
+        body += len;
+    } else if trimmed.starts_with('>') {
+        styles.push((indent..line.len(), Style::default().add_modifier(Modifier::ITALIC)));
+        styles.push((indent..indent + 1, marker));
+        body += 1;
+    }
+
+    let bytes = line.as_bytes();
+    let mut i = body;
+    while i < bytes.len() {
+        // Delimiters are ASCII, so every position checked here is a char boundary
+        let code = Style::default().fg(theme.negative).bg(theme.code_bg);
+        let (open, inner) = match bytes[i] {
+            b'`' => ("`", code),
+            b'*' if line[i..].starts_with("**") => {
+                ("**", Style::default().add_modifier(Modifier::BOLD))
+            }
+            b'*' => ("*", Style::default().add_modifier(Modifier::ITALIC)),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        let start = i + open.len();
+        let close = line[start..].find(open).map(|end| start + end);
+        match close {
+            // `2 * 3 * 4` isn't emphasis: the content can't start or end with a space
+            Some(close)
+                if close > start
+                    && (open == "`"
+                        || !(line[start..close].starts_with(' ')
+                            || line[start..close].ends_with(' '))) =>
+            {
+                styles.push((start..close, inner));
+                styles.push((i..start, marker));
+                styles.push((close..close + open.len(), marker));
+                i = close + open.len();
+            }
+            _ => i = start,
+        }
+    }
+
+    styles
+}
+
+/// Length of a list bullet (`- `, `* `, `+ `, `1. `, `1) `) at the start of a line
+fn list_marker_len(text: &str) -> Option<usize> {
+    if ["- ", "* ", "+ "].iter().any(|bullet| text.starts_with(bullet)) {
+        return Some(2);
+    }
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    let rest = &text[digits..];
+    (digits > 0 && (rest.starts_with(". ") || rest.starts_with(") "))).then_some(digits + 2)
+}
+
 #[derive(Debug, Clone)]
 pub struct CodeBlockInfo {
     pub start: usize,
@@ -130,6 +299,7 @@ pub struct CodeBlockInfo {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::ui::theme::ColorTheme;
 
     #[test]
     fn test_parse_code_blocks() {
@@ -148,12 +318,87 @@ mod tests {
         assert_eq!(blocks[0].language, "rust");
     }
 
+    #[test]
+    fn test_markdown_styles() {
+        let theme = ColorTheme::Default.theme();
+        let ranges = |line: &str| -> Vec<Range<usize>> {
+            markdown_styles(line, &theme).into_iter().map(|(r, _)| r).collect()
+        };
+        // Header: whole line, then the dimmed `##`
+        assert_eq!(ranges("## Plan"), vec![0..7, 0..2]);
+        // Bullet plus bold: inner text, then both markers
+        assert_eq!(ranges("- a **b** c"), vec![0..2, 6..7, 4..6, 7..9]);
+        assert_eq!(ranges("run `ls`"), vec![5..7, 4..5, 7..8]);
+        assert_eq!(ranges("1. first"), vec![0..3]);
+        // Not emphasis
+        assert!(ranges("2 * 3 * 4").is_empty());
+        assert!(ranges("#hashtag").is_empty());
+        assert!(ranges("unclosed **bold").is_empty());
+
+        let (_, bold) = &markdown_styles("**b**", &theme)[0];
+        assert!(bold.add_modifier.contains(Modifier::BOLD));
+
+        // Colors follow the active theme
+        let mono = ColorTheme::Monochrome.theme();
+        let (_, code) = &markdown_styles("`x`", &mono)[0];
+        assert_eq!(code.fg, Some(mono.negative));
+        assert_eq!(code.bg, Some(mono.code_bg));
+    }
+
+    #[test]
+    fn test_with_theme_known_and_unknown() {
+        let light = Highlighter::with_theme("base16-ocean.light");
+        assert_eq!(light.theme_name(), "base16-ocean.light");
+        assert!(light.available_themes().contains(&"Solarized (dark)"));
+
+        let fallback = Highlighter::with_theme("no-such-theme");
+        assert_eq!(fallback.theme_name(), DEFAULT_THEME);
+    }
+
+    fn to_lines(text: &str) -> Vec<String> {
+        text.lines().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_guess_language_from_body() {
+        assert_eq!(
+            guess_language(&to_lines("use std::fs;\nfn main() {\n    let mut x = 1;\n}")),
+            Some("rust")
+        );
+        assert_eq!(
+            guess_language(&to_lines("import os\ndef main():\n    pass")),
+            Some("python")
+        );
+        assert_eq!(guess_language(&to_lines("#!/bin/bash\nls")), Some("bash"));
+        assert_eq!(guess_language(&to_lines("#include <stdio.h>")), Some("cpp"));
+        assert_eq!(guess_language(&to_lines("{\"a\": 1}")), Some("json"));
+    }
+
+    #[test]
+    fn test_guess_language_leaves_prose_alone() {
+        assert_eq!(
+            guess_language(&to_lines("This is just a quoted paragraph.\nNothing to see.")),
+            None
+        );
+        assert_eq!(guess_language(&to_lines("fn x")), None);
+    }
+
+    #[test]
+    fn test_parse_code_blocks_guesses_bare_fence() {
+        let lines = to_lines("```\nimport sys\ndef f():\n    pass\n```");
+        let blocks = parse_code_blocks(&lines);
+        assert_eq!(blocks[0].language, "python");
+    }
+
     #[test]
     fn test_highlighter_supports_rust() {
         let highlighter = Highlighter::new();
-        assert!(highlighter.supports_language("rust"));
-        assert!(highlighter.supports_language("python"));
-        assert!(highlighter.supports_language("rs"));
-        assert!(highlighter.supports_language("py"));
+        // A recognised language splits the line into several styled spans
+        for lang in ["rust", "rs", "python", "py"] {
+            let lines = highlighter.highlight_code("let x = 1", lang);
+            assert!(lines[0].spans.len() > 1, "{} was not highlighted", lang);
+        }
+        let plain = highlighter.highlight_code("let x = 1", "no-such-lang");
+        assert_eq!(plain[0].spans.len(), 1);
     }
 }
@@ -1,7 +1,9 @@
 pub mod app;
 pub mod highlight;
 pub mod state;
+pub mod theme;
 
 pub use app::App;
 pub use highlight::Highlighter;
-pub use state::{SortField, UiState};
+pub use state::{Column, Filter, SortField, UiState};
+pub use theme::ColorTheme;
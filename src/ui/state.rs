@@ -1,6 +1,16 @@
-use std::collections::HashSet;
-
-use crate::session::Session;
+use chrono::{DateTime, NaiveDate, Utc};
+use fuzzy_matcher::skim::SkimMatcherV2;
+use fuzzy_matcher::FuzzyMatcher;
+use regex::Regex;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+
+use super::theme::ColorTheme;
+use crate::actions::ExportFormat;
+use crate::session::{DiffOp, RenderOptions, SearchIndex, Session, DEFAULT_CHARS_PER_TOKEN};
+use crate::utils::{file_safe, Symbols};
 
 /// Application view modes
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -10,6 +20,10 @@ pub enum View {
     Search,
     Help,
     Confirm,
+    ProjectPicker,
+    Diff,
+    /// Name prompt for archiving the selection into one tar.gz
+    ArchiveName,
 }
 
 /// Dialog action to perform on confirmation
@@ -17,16 +31,40 @@ pub enum View {
 pub enum DialogAction {
     DeleteSelected,
     DeleteOlderThan(u32),
+    ExportSelected,
+    /// Export the selected resume chain as one merged document
+    ExportMerged,
+    ArchiveSelected,
+    /// Remove the files written by the last export or archive
+    UndoLastAction,
+}
+
+/// Files written by the last export or archive, so `u` can take them back
+///
+/// Deletes are permanent and aren't recorded.
+#[derive(Debug, Clone)]
+pub struct ActionRecord {
+    /// What was done, e.g. "export of 3 session(s)"
+    pub description: String,
+    /// Files the action created (files it overwrote aren't listed, so undo keeps them)
+    pub created: Vec<PathBuf>,
+    /// Export directory whose index needs refreshing once the files are gone
+    pub export_dir: Option<PathBuf>,
 }
 
 /// Sort field options
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
 pub enum SortField {
     #[default]
     Date,
     Size,
     Project,
     Name,
+    /// When the conversation started (first message timestamp)
+    Created,
 }
 
 impl SortField {
@@ -35,7 +73,8 @@ impl SortField {
             SortField::Date => SortField::Size,
             SortField::Size => SortField::Project,
             SortField::Project => SortField::Name,
-            SortField::Name => SortField::Date,
+            SortField::Name => SortField::Created,
+            SortField::Created => SortField::Date,
         }
     }
 
@@ -45,6 +84,84 @@ impl SortField {
             SortField::Size => "Size",
             SortField::Project => "Project",
             SortField::Name => "Name",
+            SortField::Created => "Created",
+        }
+    }
+
+    /// Compare two sessions by this field, with fixed tiebreakers so ties don't jitter
+    ///
+    /// `reversed` only flips the primary key: sessions that tie still list newest first.
+    pub fn compare(self, a: &Session, b: &Session, reversed: bool) -> Ordering {
+        fn name(s: &Session) -> &str {
+            s.summary
+                .as_deref()
+                .or(s.first_message.as_deref())
+                .unwrap_or("")
+        }
+        // Sessions without timestamps (or not loaded yet) fall back to the file time
+        fn created(s: &Session) -> DateTime<Utc> {
+            s.created.unwrap_or(s.modified)
+        }
+        let primary = match self {
+            SortField::Date => b.modified.cmp(&a.modified),
+            SortField::Created => created(b).cmp(&created(a)),
+            SortField::Size => b.size_bytes.cmp(&a.size_bytes),
+            SortField::Project => a.project.cmp(&b.project),
+            SortField::Name => name(a).cmp(name(b)),
+        };
+        let primary = if reversed { primary.reverse() } else { primary };
+
+        let by_project = match self {
+            SortField::Size => a.project.cmp(&b.project),
+            _ => Ordering::Equal,
+        };
+        primary
+            .then(by_project)
+            .then_with(|| b.modified.cmp(&a.modified))
+            .then_with(|| a.id.cmp(&b.id))
+    }
+}
+
+/// Columns of the session table, selected and ordered with `--columns`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Column {
+    Project,
+    Date,
+    Created,
+    Age,
+    Size,
+    Msgs,
+    Tokens,
+    Branch,
+    Id,
+    Preview,
+}
+
+impl Column {
+    /// Columns shown when none are configured
+    pub const DEFAULT: &'static [Column] = &[
+        Column::Project,
+        Column::Date,
+        Column::Age,
+        Column::Size,
+        Column::Msgs,
+        Column::Tokens,
+        Column::Preview,
+    ];
+
+    pub fn title(self) -> &'static str {
+        match self {
+            Column::Project => "Project",
+            Column::Date => "Date",
+            Column::Created => "Created",
+            Column::Age => "Age",
+            Column::Size => "Size",
+            Column::Msgs => "Msgs",
+            Column::Tokens => "Tokens",
+            Column::Branch => "Branch",
+            Column::Id => "Session ID",
+            Column::Preview => "Preview",
         }
     }
 }
@@ -55,32 +172,200 @@ pub struct Filter {
     pub query: String,
     pub project: Option<String>,
     pub age_days: Option<u32>,
+    /// Only show pinned sessions
+    pub pinned_only: bool,
+    /// Match the query only at word boundaries
+    pub whole_word: bool,
+    /// Fuzzy-match the query against titles and previews and rank by match quality
+    pub fuzzy: bool,
+    /// Show agent sub-sessions (hidden by default)
+    pub include_agents: bool,
+    /// Show sessions with only system/meta records (hidden by default)
+    pub include_system_only: bool,
+    /// Earliest modification date to show (inclusive, UTC)
+    pub since: Option<NaiveDate>,
+    /// Latest modification date to show (inclusive, UTC)
+    pub until: Option<NaiveDate>,
+    /// Only show projects with a session modified within this many days
+    pub active_within_days: Option<u32>,
+    /// Only show sessions containing fenced code blocks
+    pub code_only: bool,
+    /// Smallest session file size to show, in bytes
+    pub min_size: Option<u64>,
+    /// Largest session file size to show, in bytes
+    pub max_size: Option<u64>,
+}
+
+impl Filter {
+    /// Check whether a modification time falls inside the since/until range
+    pub fn in_date_range(&self, modified: DateTime<Utc>) -> bool {
+        let date = modified.date_naive();
+        self.since.map_or(true, |since| date >= since)
+            && self.until.map_or(true, |until| date <= until)
+    }
+
+    /// Check whether a session file size falls inside the min/max size range
+    pub fn in_size_range(&self, bytes: u64) -> bool {
+        self.min_size.map_or(true, |min| bytes >= min)
+            && self.max_size.map_or(true, |max| bytes <= max)
+    }
+
+    /// Short label for the active size range ("" when unbounded)
+    pub fn size_range_label(&self, symbols: &Symbols) -> String {
+        let size = |bytes: u64| humansize::format_size(bytes, humansize::BINARY);
+        match (self.min_size, self.max_size) {
+            (None, None) => String::new(),
+            (Some(min), None) => format!("{}{}", symbols.at_least, size(min)),
+            (None, Some(max)) => format!("{}{}", symbols.at_most, size(max)),
+            (Some(min), Some(max)) => format!("{}..{}", size(min), size(max)),
+        }
+    }
+
+    /// Short label for the active date range ("" when unbounded)
+    pub fn date_range_label(&self) -> String {
+        match (self.since, self.until) {
+            (None, None) => String::new(),
+            (since, until) => format!(
+                "{}..{}",
+                since.map(|d| d.to_string()).unwrap_or_default(),
+                until.map(|d| d.to_string()).unwrap_or_default()
+            ),
+        }
+    }
+}
+
+/// Date range presets cycled with `w`: (label, days back including today)
+const DATE_PRESETS: [(&str, Option<i64>); 4] = [
+    ("All dates", None),
+    ("Today", Some(1)),
+    ("Last 7 days", Some(7)),
+    ("Last 30 days", Some(30)),
+];
+
+/// Minimum size presets cycled with `S`: (label, bytes)
+const SIZE_PRESETS: [(&str, Option<u64>); 4] = [
+    ("All sizes", None),
+    ("At least 100 KiB", Some(100 << 10)),
+    ("At least 1 MiB", Some(1 << 20)),
+    ("At least 10 MiB", Some(10 << 20)),
+];
+
+/// Outcome of merging a fresh scan into the current session list
+#[derive(Debug, Default)]
+pub struct MergeResult {
+    /// Indices of sessions whose metadata must be (re)loaded
+    pub stale: Vec<usize>,
+    pub added: usize,
+    pub removed: usize,
 }
 
 /// Main UI state
 pub struct UiState {
     pub view: View,
+    /// View the help overlay was opened from; its bindings are shown and `?` returns to it
+    pub help_context: View,
+    /// First line shown in the help overlay, clamped when it is drawn
+    pub help_scroll: usize,
+    /// Lines the help overlay showed at once when last drawn, for paging
+    pub help_page: usize,
     pub sessions: Vec<Session>,
     pub filtered_indices: Vec<usize>,
     pub cursor: usize,
     pub scroll_offset: usize,
+    /// Rows of the session table visible in the last drawn frame
+    pub visible_height: usize,
     pub selected: HashSet<usize>,
     pub select_mode: bool,
     pub filter: Filter,
     pub preview_scroll: usize,
+    /// Wrap long preview lines (off = horizontal scrolling)
+    pub preview_wrap: bool,
+    /// Style Markdown (headers, lists, bold, inline code) in preview text (off = raw lines)
+    pub preview_markdown: bool,
+    /// UI color preset (cycle with `T`)
+    pub color_theme: ColorTheme,
+    /// Columns scrolled right when wrapping is off
+    pub preview_hscroll: usize,
+    /// Line cursor in the preview (what `y`/`c` act on), kept on screen by the renderer
+    pub preview_cursor: usize,
     pub preview_lines: Vec<String>,
     pub preview_search: String,
     pub preview_search_active: bool,
     pub preview_matches: Vec<usize>,
+    /// Byte ranges of search matches within each matching preview line
+    pub preview_match_ranges: HashMap<usize, Vec<Range<usize>>>,
     pub preview_match_index: usize,
     pub preview_selected_sections: HashSet<usize>,
+    /// Session comparison shown in the diff view
+    pub diff_ops: Vec<DiffOp>,
+    /// Summary lines above the diff (ids, message counts, divergence point)
+    pub diff_header: Vec<String>,
+    pub diff_scroll: usize,
     pub dialog_message: Option<String>,
+    /// Last export or archive, for undo
+    pub last_action: Option<ActionRecord>,
+    /// Directory the last export or archive wrote to, opened with `L`
+    pub last_output_dir: Option<PathBuf>,
     pub dialog_action: Option<DialogAction>,
+    /// Session indices listed under the dialog message (destructive bulk actions)
+    pub dialog_targets: Vec<usize>,
     pub status_message: Option<String>,
     pub projects: Vec<String>,
     pub project_filter_index: usize,
+    /// Type-to-filter text in the project picker
+    pub picker_query: String,
+    /// Highlighted row in the project picker
+    pub picker_cursor: usize,
+    /// Name typed for a batch archive (`Z`)
+    pub archive_name: String,
+    /// Index into the date range presets (0 = all dates)
+    pub date_preset_index: usize,
+    /// Index into the size presets (0 = all sizes)
+    pub size_preset_index: usize,
     pub sort_field: SortField,
     pub sort_reversed: bool,
+    /// Show dates as "3h ago" instead of absolute
+    pub relative_time: bool,
+    /// Show the full session id in place of the preview column
+    pub show_ids: bool,
+    /// Session table columns, in display order
+    pub columns: Vec<Column>,
+    /// Pinned session ids (persisted to the bookmarks file)
+    pub pinned: HashSet<String>,
+    /// Float the most recently modified session to the top, marked active
+    pub float_active: bool,
+    /// Index of the session shown as active (set by `apply_sort`)
+    pub active_session: Option<usize>,
+    /// Format used by the export action
+    pub export_format: ExportFormat,
+    /// Export directory override (default: ~/claude-sessions-export)
+    pub export_dir: Option<PathBuf>,
+    /// File name template for exports
+    pub export_name_template: String,
+    /// Which content blocks appear in preview and exports
+    pub render_options: RenderOptions,
+    /// Age threshold for the "delete older than" action
+    pub older_than_days: u32,
+    /// Window for the recently active projects filter (`f`)
+    pub active_days: u32,
+    /// Delete confirmations warn about sessions modified this recently
+    pub protect_recent_minutes: u32,
+    /// Projects directory rescanned on refresh
+    pub projects_dir: PathBuf,
+    /// Follow symlinked project directories and session files on rescan
+    pub follow_symlinks: bool,
+    /// Command prefix that opens a new terminal window (`None`: resume in place on quit)
+    pub terminal_command: Option<String>,
+    /// Rescan the projects directory periodically (`--watch`)
+    pub watch: bool,
+    /// Divisor for token estimates when (re)loading metadata
+    pub chars_per_token: f64,
+    /// Open straight into the preview and quit when it closes (`--view`)
+    pub preview_only: bool,
+    /// Persistent term index used to rule out sessions before substring matching
+    pub search_index: SearchIndex,
+    /// Price per million tokens for the footer cost estimate (hidden when unset)
+    pub price_per_million_tokens: Option<f64>,
 }
 
 impl UiState {
@@ -90,27 +375,69 @@ impl UiState {
 
         Self {
             view: View::List,
+            help_context: View::List,
+            help_scroll: 0,
+            help_page: 0,
             sessions,
             filtered_indices,
             cursor: 0,
             scroll_offset: 0,
+            visible_height: 20,
             selected: HashSet::new(),
             select_mode: false,
             filter: Filter::default(),
             preview_scroll: 0,
+            preview_cursor: 0,
+            preview_wrap: true,
+            preview_markdown: true,
+            color_theme: ColorTheme::default(),
+            preview_hscroll: 0,
             preview_lines: Vec::new(),
             preview_search: String::new(),
             preview_search_active: false,
             preview_matches: Vec::new(),
+            preview_match_ranges: HashMap::new(),
             preview_match_index: 0,
             preview_selected_sections: HashSet::new(),
+            diff_ops: Vec::new(),
+            diff_header: Vec::new(),
+            diff_scroll: 0,
             dialog_message: None,
+            last_action: None,
+            last_output_dir: None,
             dialog_action: None,
+            dialog_targets: Vec::new(),
             status_message: None,
             projects,
             project_filter_index: 0, // 0 = All
+            picker_query: String::new(),
+            picker_cursor: 0,
+            archive_name: String::new(),
+            date_preset_index: 0,
+            size_preset_index: 0,
             sort_field: SortField::Date,
             sort_reversed: false,
+            relative_time: false,
+            show_ids: false,
+            columns: Column::DEFAULT.to_vec(),
+            pinned: HashSet::new(),
+            float_active: false,
+            active_session: None,
+            export_format: ExportFormat::default(),
+            export_dir: None,
+            export_name_template: crate::actions::DEFAULT_NAME_TEMPLATE.to_string(),
+            render_options: RenderOptions::default(),
+            older_than_days: 30,
+            active_days: 14,
+            protect_recent_minutes: 60,
+            projects_dir: PathBuf::new(),
+            follow_symlinks: true,
+            price_per_million_tokens: None,
+            terminal_command: None,
+            watch: false,
+            chars_per_token: DEFAULT_CHARS_PER_TOKEN,
+            preview_only: false,
+            search_index: SearchIndex::default(),
         }
     }
 
@@ -147,6 +474,14 @@ impl UiState {
         }
     }
 
+    /// Move cursor to a position in the filtered list (ignored if out of range)
+    pub fn move_cursor_to(&mut self, pos: usize) {
+        if pos < self.filtered_indices.len() {
+            self.cursor = pos;
+            self.adjust_scroll();
+        }
+    }
+
     /// Move cursor to top
     pub fn cursor_top(&mut self) {
         self.cursor = 0;
@@ -176,9 +511,7 @@ impl UiState {
 
     /// Adjust scroll to keep cursor visible
     fn adjust_scroll(&mut self) {
-        // Assume visible height of ~20 rows for now
-        // This will be adjusted by the UI based on actual terminal size
-        let visible_height = 20;
+        let visible_height = self.visible_height.max(1);
 
         if self.cursor < self.scroll_offset {
             self.scroll_offset = self.cursor;
@@ -189,9 +522,8 @@ impl UiState {
 
     /// Set visible height and readjust scroll
     pub fn set_visible_height(&mut self, height: usize) {
-        if self.cursor >= self.scroll_offset + height {
-            self.scroll_offset = self.cursor.saturating_sub(height - 1);
-        }
+        self.visible_height = height.max(1);
+        self.adjust_scroll();
     }
 
     /// Toggle selection on current item
@@ -212,6 +544,53 @@ impl UiState {
         }
     }
 
+    /// Index loaded content for fast searches (in this run and later CLI searches)
+    pub fn update_search_index(&mut self) {
+        for session in &self.sessions {
+            self.search_index.update(session);
+        }
+        self.search_index.prune_missing();
+        if let Err(e) = self.search_index.save() {
+            self.set_status(format!("Failed to save search index: {:#}", e));
+        }
+    }
+
+    /// Session count, total bytes and estimated tokens across the filtered sessions
+    pub fn filtered_totals(&self) -> (usize, u64, usize) {
+        self.filtered_indices
+            .iter()
+            .map(|&idx| &self.sessions[idx])
+            .fold((0, 0, 0), |(count, bytes, tokens), s| {
+                (count + 1, bytes + s.size_bytes, tokens + s.token_count.unwrap_or(0))
+            })
+    }
+
+    /// Select every visible empty session, returning how many were added
+    pub fn select_empty(&mut self) -> usize {
+        self.select_matching(Session::is_empty)
+    }
+
+    /// Add every visible session matching `predicate` to the selection, returning how
+    /// many were newly selected
+    pub fn select_matching(&mut self, predicate: impl Fn(&Session) -> bool) -> usize {
+        let before = self.selected.len();
+        for &idx in &self.filtered_indices {
+            if predicate(&self.sessions[idx]) {
+                self.selected.insert(idx);
+            }
+        }
+        self.selected.len() - before
+    }
+
+    /// Select the visible sessions that aren't selected and deselect those that are
+    pub fn invert_selection(&mut self) {
+        for &idx in &self.filtered_indices {
+            if !self.selected.remove(&idx) {
+                self.selected.insert(idx);
+            }
+        }
+    }
+
     /// Clear selection
     pub fn clear_selection(&mut self) {
         self.selected.clear();
@@ -230,18 +609,57 @@ impl UiState {
             .collect()
     }
 
+    /// Sessions an action applies to: the selection, or the current session if none selected
+    pub fn action_targets(&self) -> Vec<&Session> {
+        if self.selected.is_empty() {
+            self.current_session().into_iter().collect()
+        } else {
+            self.get_selected_sessions()
+        }
+    }
+
     /// Apply filters and update filtered_indices
     pub fn apply_filters(&mut self) {
-        use chrono::Utc;
-
+        let current_path = self.current_session().map(|s| s.path.clone());
         let now = Utc::now();
         let query_lower = self.filter.query.to_lowercase();
+        let candidates = self.search_index.candidates(&query_lower);
+        let word_regex = if self.filter.whole_word && !query_lower.is_empty() {
+            Regex::new(&format!(r"\b{}\b", regex::escape(&query_lower))).ok()
+        } else {
+            None
+        };
+        let matches_query = |text: &str| match word_regex {
+            Some(ref re) => re.is_match(text),
+            None => text.contains(&query_lower),
+        };
+        let fuzzy_matcher = (self.filter.fuzzy && !query_lower.is_empty())
+            .then(|| SkimMatcherV2::default().ignore_case());
+        let mut fuzzy_scores: HashMap<usize, i64> = HashMap::new();
+
+        // Projects whose newest session is recent enough for the active filter
+        let active_projects: Option<HashSet<&str>> = self.filter.active_within_days.map(|days| {
+            let mut latest: HashMap<&str, DateTime<Utc>> = HashMap::new();
+            for session in &self.sessions {
+                let entry = latest
+                    .entry(session.project.as_str())
+                    .or_insert(session.modified);
+                *entry = (*entry).max(session.modified);
+            }
+            latest
+                .into_iter()
+                .filter(|(_, modified)| {
+                    now.signed_duration_since(*modified).num_days() < days as i64
+                })
+                .map(|(project, _)| project)
+                .collect()
+        });
 
         self.filtered_indices = self
             .sessions
             .iter()
             .enumerate()
-            .filter(|(_, session)| {
+            .filter(|(idx, session)| {
                 // Project filter
                 if let Some(ref proj) = self.filter.project {
                     if &session.project != proj {
@@ -249,6 +667,43 @@ impl UiState {
                     }
                 }
 
+                // Recently active projects
+                if let Some(ref active) = active_projects {
+                    if !active.contains(session.project.as_str()) {
+                        return false;
+                    }
+                }
+
+                // Pinned filter
+                if self.filter.pinned_only && !self.pinned.contains(&session.id) {
+                    return false;
+                }
+
+                // Agent sub-sessions
+                if session.is_agent && !self.filter.include_agents {
+                    return false;
+                }
+
+                // Sessions with nothing but hook output or injected context
+                if session.system_only && !self.filter.include_system_only {
+                    return false;
+                }
+
+                // Code blocks filter
+                if self.filter.code_only && !session.has_code {
+                    return false;
+                }
+
+                // Date range filter
+                if !self.filter.in_date_range(session.modified) {
+                    return false;
+                }
+
+                // Size range filter
+                if !self.filter.in_size_range(session.size_bytes) {
+                    return false;
+                }
+
                 // Age filter
                 if let Some(days) = self.filter.age_days {
                     let age = now.signed_duration_since(session.modified);
@@ -257,11 +712,24 @@ impl UiState {
                     }
                 }
 
-                // Query filter (case-insensitive substring match on full content)
+                // Fuzzy query: titles and previews only, full bodies would be too slow
+                if let Some(ref matcher) = fuzzy_matcher {
+                    let Some(score) = matcher.fuzzy_match(&fuzzy_text(session), &query_lower)
+                    else {
+                        return false;
+                    };
+                    fuzzy_scores.insert(*idx, score);
+                    return true;
+                }
+
+                // Query filter (case-insensitive substring or whole-word match on full content)
                 if !query_lower.is_empty() {
                     // Search full content if available, otherwise fall back to metadata
                     let matches = if let Some(ref content) = session.search_content {
-                        content.contains(&query_lower)
+                        candidates
+                            .as_ref()
+                            .map_or(true, |c| self.search_index.may_match(session, c))
+                            && matches_query(content)
                     } else {
                         let search_text = format!(
                             "{} {} {} {}",
@@ -271,7 +739,7 @@ impl UiState {
                             session.first_message.as_deref().unwrap_or("")
                         )
                         .to_lowercase();
-                        search_text.contains(&query_lower)
+                        matches_query(&search_text)
                     };
                     if !matches {
                         return false;
@@ -283,11 +751,29 @@ impl UiState {
             .map(|(idx, _)| idx)
             .collect();
 
-        // Reset cursor if out of bounds
-        if self.cursor >= self.filtered_indices.len() {
-            self.cursor = self.filtered_indices.len().saturating_sub(1);
+        self.apply_sort();
+        if !fuzzy_scores.is_empty() {
+            // Best matches first; the sort order breaks ties and pins stay on top
+            let pinned = &self.pinned;
+            let sessions = &self.sessions;
+            self.filtered_indices.sort_by_key(|idx| {
+                (!pinned.contains(&sessions[*idx].id), Reverse(fuzzy_scores[idx]))
+            });
+        }
+
+        // Stay on the same session while it's still visible; otherwise start from the top
+        let current_pos = current_path.and_then(|path| {
+            self.filtered_indices
+                .iter()
+                .position(|&idx| self.sessions[idx].path == path)
+        });
+        match current_pos {
+            Some(pos) => {
+                self.cursor = pos;
+                self.adjust_scroll();
+            }
+            None => self.scroll_offset = 0,
         }
-        self.scroll_offset = 0;
     }
 
     /// Cycle project filter
@@ -303,6 +789,92 @@ impl UiState {
         self.apply_filters();
     }
 
+    /// Show help for the current view
+    pub fn open_help(&mut self) {
+        self.help_context = self.view;
+        self.help_scroll = 0;
+        self.view = View::Help;
+    }
+
+    /// Leave help for the view it was opened from
+    pub fn close_help(&mut self) {
+        self.view = self.help_context;
+    }
+
+    /// Prompt for a batch archive name, suggesting the project filter and today's date
+    pub fn open_archive_name_prompt(&mut self) {
+        let prefix = self.filter.project.as_deref().map(file_safe);
+        self.archive_name = format!(
+            "{}_{}",
+            prefix.as_deref().unwrap_or("sessions"),
+            Utc::now().format("%Y-%m-%d")
+        );
+        self.view = View::ArchiveName;
+    }
+
+    /// Open the project picker with an empty query
+    pub fn open_project_picker(&mut self) {
+        self.picker_query.clear();
+        self.picker_cursor = 0;
+        self.view = View::ProjectPicker;
+    }
+
+    /// Project picker rows matching the query: "All" (`None`) first, then projects,
+    /// each with its session count
+    pub fn project_picker_entries(&self) -> Vec<(Option<&str>, usize)> {
+        let visible: Vec<&Session> = self
+            .sessions
+            .iter()
+            .filter(|s| !s.is_agent || self.filter.include_agents)
+            .collect();
+        let query = self.picker_query.to_lowercase();
+
+        let mut entries = Vec::new();
+        if query.is_empty() {
+            entries.push((None, visible.len()));
+        }
+        for project in &self.projects {
+            if !project.to_lowercase().contains(&query) {
+                continue;
+            }
+            let count = visible.iter().filter(|s| &s.project == project).count();
+            entries.push((Some(project.as_str()), count));
+        }
+        entries
+    }
+
+    /// Apply the highlighted picker row as the project filter and return to the list
+    pub fn select_picker_entry(&mut self) {
+        let choice = self
+            .project_picker_entries()
+            .get(self.picker_cursor)
+            .map(|(name, _)| name.map(str::to_string));
+
+        match choice {
+            Some(Some(name)) => {
+                self.set_project_filter(&name);
+            }
+            Some(None) => {
+                self.project_filter_index = 0;
+                self.filter.project = None;
+                self.apply_filters();
+            }
+            None => return,
+        }
+        self.view = View::List;
+    }
+
+    /// Filter to a project by name; returns false (leaving "All") if it no longer exists
+    pub fn set_project_filter(&mut self, name: &str) -> bool {
+        let Some(pos) = self.projects.iter().position(|p| p == name) else {
+            return false;
+        };
+        self.project_filter_index = pos + 1;
+        self.filter.project = Some(name.to_string());
+        self.apply_filters();
+        true
+    }
+
     /// Get current project filter display name
     pub fn current_project_filter(&self) -> &str {
         if self.project_filter_index == 0 {
@@ -319,7 +891,7 @@ impl UiState {
         self.set_status(format!(
             "Sort: {} {}",
             self.sort_field.as_str(),
-            if self.sort_reversed { "↑" } else { "↓" }
+            self.render_options.symbols().sort_arrow(self.sort_reversed)
         ));
     }
 
@@ -330,40 +902,39 @@ impl UiState {
         self.set_status(format!(
             "Sort: {} {}",
             self.sort_field.as_str(),
-            if self.sort_reversed { "↑" } else { "↓" }
+            self.render_options.symbols().sort_arrow(self.sort_reversed)
         ));
     }
 
     /// Apply current sort to filtered indices
     pub fn apply_sort(&mut self) {
+        // The session you were just in: the newest file that isn't an agent sub-session
+        self.active_session = self
+            .float_active
+            .then(|| {
+                self.sessions
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, s)| !s.is_agent)
+                    .max_by_key(|(_, s)| s.modified)
+                    .map(|(idx, _)| idx)
+            })
+            .flatten();
+
         let sessions = &self.sessions;
+        let pinned = &self.pinned;
+        let active = self.active_session;
         let sort_field = self.sort_field;
         let reversed = self.sort_reversed;
 
         self.filtered_indices.sort_by(|&a, &b| {
-            let cmp = match sort_field {
-                SortField::Date => sessions[b].modified.cmp(&sessions[a].modified),
-                SortField::Size => sessions[b].size_bytes.cmp(&sessions[a].size_bytes),
-                SortField::Project => sessions[a].project.cmp(&sessions[b].project),
-                SortField::Name => {
-                    let name_a = sessions[a]
-                        .summary
-                        .as_deref()
-                        .or(sessions[a].first_message.as_deref())
-                        .unwrap_or("");
-                    let name_b = sessions[b]
-                        .summary
-                        .as_deref()
-                        .or(sessions[b].first_message.as_deref())
-                        .unwrap_or("");
-                    name_a.cmp(name_b)
-                }
-            };
-            if reversed {
-                cmp.reverse()
-            } else {
-                cmp
-            }
+            // The active session goes first, then pinned sessions, regardless of sort
+            let pin_a = pinned.contains(&sessions[a].id);
+            let pin_b = pinned.contains(&sessions[b].id);
+            (active == Some(b))
+                .cmp(&(active == Some(a)))
+                .then(pin_b.cmp(&pin_a))
+                .then_with(|| sort_field.compare(&sessions[a], &sessions[b], reversed))
         });
 
         // Reset cursor if out of bounds
@@ -372,6 +943,172 @@ impl UiState {
         }
     }
 
+    /// Toggle whole-word matching for the search query
+    pub fn toggle_whole_word(&mut self) {
+        self.filter.whole_word = !self.filter.whole_word;
+        self.filter.fuzzy = false;
+        self.apply_filters();
+    }
+
+    /// Toggle fuzzy matching (titles and previews, ranked by score) for the search query
+    pub fn toggle_fuzzy(&mut self) {
+        self.filter.fuzzy = !self.filter.fuzzy;
+        self.filter.whole_word = false;
+        self.apply_filters();
+    }
+
+    /// Show or hide agent sub-sessions
+    pub fn toggle_agents(&mut self) {
+        self.filter.include_agents = !self.filter.include_agents;
+        self.apply_filters();
+        self.set_status(format!(
+            "Agent sessions: {}",
+            if self.filter.include_agents { "shown" } else { "hidden" }
+        ));
+    }
+
+    /// Switch to the next UI color preset
+    pub fn cycle_color_theme(&mut self) {
+        self.color_theme = self.color_theme.next();
+        self.set_status(format!(
+            "Color theme: {} (set color_theme in config.toml to keep it)",
+            self.color_theme.as_str()
+        ));
+    }
+
+    /// Show or hide sessions that contain only system/meta records
+    pub fn toggle_system_only(&mut self) {
+        self.filter.include_system_only = !self.filter.include_system_only;
+        self.apply_filters();
+        self.set_status(format!(
+            "System-only sessions: {}",
+            if self.filter.include_system_only { "shown" } else { "hidden" }
+        ));
+    }
+
+    /// Toggle floating the most recently modified session to the top
+    pub fn toggle_float_active(&mut self) {
+        self.float_active = !self.float_active;
+        self.apply_sort();
+        self.set_status(format!(
+            "Active session on top: {}",
+            if self.float_active { "on" } else { "off" }
+        ));
+    }
+
+    /// Toggle showing only sessions that contain fenced code blocks
+    pub fn toggle_code_only(&mut self) {
+        self.filter.code_only = !self.filter.code_only;
+        self.apply_filters();
+        self.set_status(
+            if self.filter.code_only {
+                "Showing sessions with code blocks"
+            } else {
+                "Showing all sessions"
+            }
+            .to_string(),
+        );
+    }
+
+    /// Cycle the date range filter through the presets
+    pub fn cycle_date_range(&mut self) {
+        self.date_preset_index = (self.date_preset_index + 1) % DATE_PRESETS.len();
+        let (label, days) = DATE_PRESETS[self.date_preset_index];
+
+        let today = Utc::now().date_naive();
+        self.filter.since = days.map(|d| today - chrono::Duration::days(d - 1));
+        self.filter.until = None;
+        self.apply_filters();
+        self.set_status(format!("Showing: {}", label));
+    }
+
+    /// Cycle through minimum size presets (all, 100 KiB, 1 MiB, 10 MiB)
+    pub fn cycle_size_filter(&mut self) {
+        self.size_preset_index = (self.size_preset_index + 1) % SIZE_PRESETS.len();
+        let (label, min_size) = SIZE_PRESETS[self.size_preset_index];
+
+        self.filter.min_size = min_size;
+        self.filter.max_size = None;
+        self.apply_filters();
+        self.set_status(format!("Showing: {}", label));
+    }
+
+    /// Toggle relative/absolute date display
+    pub fn toggle_relative_time(&mut self) {
+        self.relative_time = !self.relative_time;
+        self.set_status(format!(
+            "Dates: {}",
+            if self.relative_time { "relative" } else { "absolute" }
+        ));
+    }
+
+    /// Toggle between UTC and local time for dates and preview timestamps
+    pub fn toggle_local_time(&mut self) {
+        self.render_options.local_time = !self.render_options.local_time;
+        self.set_status(format!(
+            "Times: {}",
+            if self.render_options.local_time { "local" } else { "UTC" }
+        ));
+    }
+
+    /// Toggle the preview column between conversation preview and session id
+    pub fn toggle_show_ids(&mut self) {
+        self.show_ids = !self.show_ids;
+        self.set_status(format!(
+            "Last column: {}",
+            if self.show_ids { "session id" } else { "preview" }
+        ));
+    }
+
+    /// Check if a session is pinned
+    pub fn is_pinned(&self, idx: usize) -> bool {
+        self.sessions
+            .get(idx)
+            .is_some_and(|s| self.pinned.contains(&s.id))
+    }
+
+    /// Toggle pin on the current session, returning the new pinned state
+    pub fn toggle_pin(&mut self) -> Option<bool> {
+        let id = self.current_session()?.id.clone();
+        let now_pinned = if self.pinned.remove(&id) {
+            false
+        } else {
+            self.pinned.insert(id.clone());
+            true
+        };
+
+        self.apply_sort();
+        // Keep the cursor on the session that moved
+        if let Some(pos) = self
+            .filtered_indices
+            .iter()
+            .position(|&idx| self.sessions[idx].id == id)
+        {
+            self.cursor = pos;
+            self.adjust_scroll();
+        }
+        Some(now_pinned)
+    }
+
+    /// Toggle showing only projects active within `active_days`
+    pub fn toggle_active_projects(&mut self) {
+        self.filter.active_within_days = match self.filter.active_within_days {
+            Some(_) => None,
+            None => Some(self.active_days),
+        };
+        self.apply_filters();
+        self.set_status(match self.filter.active_within_days {
+            Some(days) => format!("Showing projects active in the last {} days", days),
+            None => "Showing all projects".to_string(),
+        });
+    }
+
+    /// Toggle showing only pinned sessions
+    pub fn toggle_pinned_only(&mut self) {
+        self.filter.pinned_only = !self.filter.pinned_only;
+        self.apply_filters();
+    }
+
     /// Show confirmation dialog
     pub fn show_confirm(&mut self, message: String, action: DialogAction) {
         self.dialog_message = Some(message);
@@ -379,10 +1116,22 @@ impl UiState {
         self.view = View::Confirm;
     }
 
+    /// Show a confirmation dialog that lists the sessions it will affect
+    pub fn show_confirm_with_targets(
+        &mut self,
+        message: String,
+        action: DialogAction,
+        targets: Vec<usize>,
+    ) {
+        self.dialog_targets = targets;
+        self.show_confirm(message, action);
+    }
+
     /// Clear dialog
     pub fn clear_dialog(&mut self) {
         self.dialog_message = None;
         self.dialog_action = None;
+        self.dialog_targets.clear();
         self.view = View::List;
     }
 
@@ -419,9 +1168,96 @@ impl UiState {
         self.projects = crate::session::get_project_names(&self.sessions);
     }
 
+    /// Merge a fresh scan into the session list, keeping parsed metadata for
+    /// unchanged files and preserving cursor and selection by path
+    pub fn merge_sessions(&mut self, fresh: Vec<Session>) -> MergeResult {
+        let current_path = self.current_session().map(|s| s.path.clone());
+        let selected_paths: HashSet<PathBuf> = self
+            .get_selected_sessions()
+            .iter()
+            .map(|s| s.path.clone())
+            .collect();
+
+        let mut existing: HashMap<PathBuf, Session> = self
+            .sessions
+            .drain(..)
+            .map(|s| (s.path.clone(), s))
+            .collect();
+        // A renamed project directory moves files without changing them: match those by id
+        let old_paths: HashMap<String, PathBuf> = existing
+            .iter()
+            .map(|(path, s)| (s.id.clone(), path.clone()))
+            .collect();
+
+        let mut result = MergeResult::default();
+        for (idx, session) in fresh.into_iter().enumerate() {
+            let old = existing.remove(&session.path).or_else(|| {
+                let old_path = old_paths.get(&session.id).filter(|p| !p.exists())?;
+                existing.remove(old_path)
+            });
+            match old {
+                Some(mut old)
+                    if old.modified == session.modified && old.size_bytes == session.size_bytes =>
+                {
+                    old.relocate(&session);
+                    self.sessions.push(old);
+                }
+                Some(_) => {
+                    result.stale.push(idx);
+                    self.sessions.push(session);
+                }
+                None => {
+                    result.added += 1;
+                    result.stale.push(idx);
+                    self.sessions.push(session);
+                }
+            }
+        }
+        result.removed = existing.len();
+
+        self.selected = self
+            .sessions
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| selected_paths.contains(&s.path))
+            .map(|(idx, _)| idx)
+            .collect();
+
+        // Keep the project filter if that project still exists
+        self.projects = crate::session::get_project_names(&self.sessions);
+        match self
+            .filter
+            .project
+            .as_ref()
+            .and_then(|p| self.projects.iter().position(|name| name == p))
+        {
+            Some(pos) => self.project_filter_index = pos + 1,
+            None => {
+                self.filter.project = None;
+                self.project_filter_index = 0;
+            }
+        }
+
+        self.apply_filters();
+
+        if let Some(path) = current_path {
+            if let Some(pos) = self
+                .filtered_indices
+                .iter()
+                .position(|&idx| self.sessions[idx].path == path)
+            {
+                self.cursor = pos;
+                self.adjust_scroll();
+            }
+        }
+
+        result
+    }
+
     /// Update preview search and find matches
     pub fn update_preview_search(&mut self) {
         self.preview_matches.clear();
+        self.preview_match_ranges.clear();
         self.preview_match_index = 0;
 
         if self.preview_search.is_empty() {
@@ -430,14 +1266,16 @@ impl UiState {
 
         let query = self.preview_search.to_lowercase();
         for (i, line) in self.preview_lines.iter().enumerate() {
-            if line.to_lowercase().contains(&query) {
+            let ranges = crate::utils::find_match_ranges(line, &query);
+            if !ranges.is_empty() {
                 self.preview_matches.push(i);
+                self.preview_match_ranges.insert(i, ranges);
             }
         }
 
         // Jump to first match
         if !self.preview_matches.is_empty() {
-            self.preview_scroll = self.preview_matches[0];
+            self.jump_preview_to(self.preview_matches[0]);
         }
     }
 
@@ -447,7 +1285,7 @@ impl UiState {
             return;
         }
         self.preview_match_index = (self.preview_match_index + 1) % self.preview_matches.len();
-        self.preview_scroll = self.preview_matches[self.preview_match_index];
+        self.jump_preview_to(self.preview_matches[self.preview_match_index]);
     }
 
     /// Go to previous search match
@@ -460,7 +1298,7 @@ impl UiState {
         } else {
             self.preview_match_index -= 1;
         }
-        self.preview_scroll = self.preview_matches[self.preview_match_index];
+        self.jump_preview_to(self.preview_matches[self.preview_match_index]);
     }
 
     /// Clear preview search
@@ -468,9 +1306,39 @@ impl UiState {
         self.preview_search.clear();
         self.preview_search_active = false;
         self.preview_matches.clear();
+        self.preview_match_ranges.clear();
         self.preview_match_index = 0;
     }
 
+    /// Move the preview cursor, clamped to the content
+    pub fn set_preview_cursor(&mut self, line: usize) {
+        self.preview_cursor = line.min(self.preview_lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so `line` is at the top and put the cursor on it
+    pub fn jump_preview_to(&mut self, line: usize) {
+        self.set_preview_cursor(line);
+        self.preview_scroll = self.preview_cursor;
+    }
+
+    /// Body of the message under the preview cursor (without its role header)
+    pub fn get_message_at_cursor(&self) -> Option<String> {
+        let boundaries = self.get_section_boundaries();
+        let section = self.current_section_index()?;
+        let start = boundaries[section];
+        if self.preview_cursor < start {
+            return None;
+        }
+        let end = boundaries
+            .get(section + 1)
+            .copied()
+            .unwrap_or(self.preview_lines.len());
+
+        let body = self.preview_lines[start + 1..end].join("\n");
+        let body = body.trim_matches('\n');
+        (!body.trim().is_empty()).then(|| body.to_string())
+    }
+
     /// Find section boundaries in preview lines (sections start with [User], [Assistant], [System])
     pub fn get_section_boundaries(&self) -> Vec<usize> {
         self.preview_lines
@@ -485,15 +1353,15 @@ impl UiState {
             .collect()
     }
 
-    /// Get the current section index based on scroll position
+    /// Get the current section index based on the preview cursor
     pub fn current_section_index(&self) -> Option<usize> {
         let boundaries = self.get_section_boundaries();
         if boundaries.is_empty() {
             return None;
         }
-        // Find which section the scroll position is in
+        // Find which section the cursor is in
         for (i, &start) in boundaries.iter().enumerate().rev() {
-            if self.preview_scroll >= start {
+            if self.preview_cursor >= start {
                 return Some(i);
             }
         }
@@ -508,14 +1376,14 @@ impl UiState {
         }
         // Find current section and go to previous
         for &start in boundaries.iter().rev() {
-            if start < self.preview_scroll {
-                self.preview_scroll = start;
+            if start < self.preview_cursor {
+                self.jump_preview_to(start);
                 return;
             }
         }
         // Already at first section, stay there
         if let Some(&first) = boundaries.first() {
-            self.preview_scroll = first;
+            self.jump_preview_to(first);
         }
     }
 
@@ -525,10 +1393,10 @@ impl UiState {
         if boundaries.is_empty() {
             return;
         }
-        // Find next section after current scroll
+        // Find next section after the cursor
         for &start in &boundaries {
-            if start > self.preview_scroll {
-                self.preview_scroll = start;
+            if start > self.preview_cursor {
+                self.jump_preview_to(start);
                 return;
             }
         }
@@ -546,7 +1414,7 @@ impl UiState {
         let mut section_start = 0;
         let mut section_idx = 0;
         for (i, &start) in boundaries.iter().enumerate() {
-            if self.preview_scroll >= start {
+            if self.preview_cursor >= start {
                 section_start = start;
                 section_idx = i;
             } else {
@@ -687,3 +1555,423 @@ impl UiState {
         )
     }
 }
+
+/// Text a fuzzy query is matched against: project, title and first message
+fn fuzzy_text(session: &Session) -> String {
+    format!(
+        "{} {} {} {}",
+        session.project,
+        session.custom_title.as_deref().unwrap_or(""),
+        session.summary.as_deref().unwrap_or(""),
+        session.first_message.as_deref().unwrap_or("")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn make_session(id: &str, age_minutes: i64) -> Session {
+        Session {
+            size_bytes: 100,
+            modified: Utc::now() - Duration::minutes(age_minutes),
+            ..Session::test(id)
+        }
+    }
+
+    #[test]
+    fn test_project_picker_filters_and_selects() {
+        let mut other = make_session("b", 2);
+        other.project = "other".to_string();
+        let mut state = UiState::new(vec![make_session("a", 1), make_session("c", 3), other]);
+
+        state.open_project_picker();
+        assert_eq!(
+            state.project_picker_entries(),
+            vec![(None, 3), (Some("other"), 1), (Some("proj"), 2)]
+        );
+
+        state.picker_query = "PRO".to_string();
+        assert_eq!(state.project_picker_entries(), vec![(Some("proj"), 2)]);
+
+        state.select_picker_entry();
+        assert_eq!(state.view, View::List);
+        assert_eq!(state.current_project_filter(), "proj");
+        assert_eq!(state.filtered_indices.len(), 2);
+    }
+
+    #[test]
+    fn test_set_project_filter_ignores_missing_project() {
+        let mut state = UiState::new(vec![make_session("a", 1)]);
+        assert!(!state.set_project_filter("gone"));
+        assert_eq!(state.current_project_filter(), "All");
+
+        assert!(state.set_project_filter("proj"));
+        assert_eq!(state.current_project_filter(), "proj");
+        assert_eq!(state.filtered_indices, vec![0]);
+    }
+
+    #[test]
+    fn test_date_range_filter_is_inclusive() {
+        let mut state = UiState::new(vec![make_session("a", 0), make_session("b", 60 * 24 * 10)]);
+        let today = Utc::now().date_naive();
+
+        state.filter.since = Some(today - Duration::days(3));
+        state.apply_filters();
+        assert_eq!(state.filtered_indices, vec![0]);
+
+        state.filter.since = None;
+        state.filter.until = Some(today - Duration::days(10));
+        state.apply_filters();
+        assert_eq!(state.filtered_indices, vec![1]);
+    }
+
+    #[test]
+    fn test_message_at_cursor() {
+        let mut state = UiState::new(Vec::new());
+        state.preview_lines = [
+            "[User] 10:00",
+            "question",
+            "",
+            "[Assistant] 10:01",
+            "line one",
+            "line two",
+            "",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        state.set_preview_cursor(5);
+        assert_eq!(state.get_message_at_cursor().as_deref(), Some("line one\nline two"));
+
+        state.set_preview_cursor(0);
+        assert_eq!(state.get_message_at_cursor().as_deref(), Some("question"));
+
+        state.next_section();
+        assert_eq!(state.preview_cursor, 3);
+    }
+
+    #[test]
+    fn test_filtered_totals_follow_filters() {
+        let mut a = make_session("a", 1);
+        a.token_count = Some(100);
+        let mut b = make_session("agent-b", 2);
+        b.token_count = Some(50);
+        let mut state = UiState::new(vec![a, b]);
+
+        assert_eq!(state.filtered_totals(), (2, 200, 150));
+        state.apply_filters(); // hides the agent session
+        assert_eq!(state.filtered_totals(), (1, 100, 100));
+    }
+
+    #[test]
+    fn test_invert_selection_only_touches_visible() {
+        let mut state = UiState::new(vec![
+            make_session("a", 1),
+            make_session("b", 2),
+            make_session("agent-c", 3),
+        ]);
+        state.apply_filters(); // hides the agent session (index 2)
+        state.selected.insert(0);
+        state.selected.insert(2);
+
+        state.invert_selection();
+        assert_eq!(state.selected, HashSet::from([1, 2]));
+    }
+
+    #[test]
+    fn test_select_matching() {
+        let mut state = UiState::new(vec![make_session("a", 1), make_session("b", 2)]);
+        assert_eq!(state.select_matching(|s| s.id == "b"), 1);
+        assert_eq!(state.select_matching(|s| s.id == "b"), 0);
+        assert_eq!(state.selected, HashSet::from([1]));
+    }
+
+    #[test]
+    fn test_select_empty() {
+        let mut empty = make_session("a", 1);
+        empty.message_count = Some(0);
+        let mut full = make_session("b", 2);
+        full.message_count = Some(3);
+        full.first_message = Some("hi".to_string());
+        let mut state = UiState::new(vec![empty, full]);
+
+        assert_eq!(state.select_empty(), 1);
+        assert!(state.is_selected(0));
+        assert!(!state.is_selected(1));
+    }
+
+    #[test]
+    fn test_whole_word_search() {
+        let mut a = make_session("a", 1);
+        a.search_content = Some("concatenate the files".to_string());
+        let mut b = make_session("b", 2);
+        b.search_content = Some("the cat sat".to_string());
+        let mut state = UiState::new(vec![a, b]);
+
+        state.filter.query = "cat".to_string();
+        state.apply_filters();
+        assert_eq!(state.filtered_indices, vec![0, 1]);
+
+        state.toggle_whole_word();
+        assert_eq!(state.filtered_indices, vec![1]);
+    }
+
+    #[test]
+    fn test_filter_keeps_cursor_on_visible_session() {
+        let mut sessions: Vec<Session> =
+            (0..4).map(|i| make_session(&format!("s{}", i), i)).collect();
+        sessions[1].first_message = Some("fix parser".to_string());
+        sessions[3].first_message = Some("parser tests".to_string());
+        let mut state = UiState::new(sessions);
+        state.move_cursor_to(3);
+
+        state.filter.query = "parser".to_string();
+        state.apply_filters();
+        assert_eq!(state.current_session().unwrap().id, "s3");
+        assert_eq!(state.cursor, 1);
+
+        // Filtered out: the cursor falls back to the list
+        state.filter.query = "fix".to_string();
+        state.apply_filters();
+        assert_eq!(state.current_session().unwrap().id, "s1");
+        assert_eq!(state.scroll_offset, 0);
+    }
+
+    #[test]
+    fn test_size_filter() {
+        let sizes = [10, 2 << 20, 200 << 10];
+        let sessions = sizes
+            .iter()
+            .enumerate()
+            .map(|(i, &size)| {
+                let mut s = make_session(&i.to_string(), i as i64);
+                s.size_bytes = size;
+                s
+            })
+            .collect();
+        let mut state = UiState::new(sessions);
+
+        state.cycle_size_filter();
+        assert_eq!(state.filtered_indices, vec![1, 2]);
+        state.cycle_size_filter();
+        assert_eq!(state.filtered_indices, vec![1]);
+
+        state.filter.min_size = Some(1 << 10);
+        state.filter.max_size = Some(1 << 20);
+        state.apply_filters();
+        assert_eq!(state.filtered_indices, vec![2]);
+        assert_eq!(state.filter.size_range_label(&Symbols::UNICODE), "1 KiB..1 MiB");
+    }
+
+    #[test]
+    fn test_fuzzy_search_ranks_by_match_quality() {
+        let mut scattered = make_session("a", 1);
+        scattered.first_message = Some("prepare a summary report".to_string());
+        let mut close = make_session("b", 2);
+        close.first_message = Some("fix the parser".to_string());
+        let mut other = make_session("c", 3);
+        other.first_message = Some("hello".to_string());
+        let mut state = UiState::new(vec![scattered, close, other]);
+
+        state.filter.query = "parsr".to_string();
+        state.apply_filters();
+        assert!(state.filtered_indices.is_empty());
+
+        state.toggle_fuzzy();
+        assert_eq!(state.filtered_indices, vec![1, 0]);
+    }
+
+    #[test]
+    fn test_active_projects_filter_uses_newest_session() {
+        let mut dormant = make_session("old", 60 * 24 * 40);
+        dormant.project = "dormant".to_string();
+        // An old session in a project that is still active stays visible
+        let old_active = make_session("old-active", 60 * 24 * 40);
+        let mut state = UiState::new(vec![make_session("new", 5), old_active, dormant]);
+        state.apply_filters();
+        assert_eq!(state.filtered_indices.len(), 3);
+
+        state.toggle_active_projects();
+        let ids: Vec<&str> = state
+            .filtered_indices
+            .iter()
+            .map(|&i| state.sessions[i].id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["new", "old-active"]);
+
+        state.toggle_active_projects();
+        assert_eq!(state.filtered_indices.len(), 3);
+    }
+
+    #[test]
+    fn test_agent_sessions_hidden_until_toggled() {
+        let mut state = UiState::new(vec![make_session("a", 1), make_session("agent-x", 2)]);
+        state.apply_filters();
+        assert_eq!(state.filtered_indices, vec![0]);
+
+        state.toggle_agents();
+        assert_eq!(state.filtered_indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_system_only_sessions_hidden_until_toggled() {
+        let mut hooks = make_session("hooks", 2);
+        hooks.system_only = true;
+        let mut state = UiState::new(vec![make_session("a", 1), hooks]);
+        state.apply_filters();
+        assert_eq!(state.filtered_indices, vec![0]);
+
+        state.toggle_system_only();
+        assert_eq!(state.filtered_indices, vec![0, 1]);
+    }
+
+    #[test]
+    fn test_merge_sessions_keeps_unchanged_metadata() {
+        let a = make_session("a", 1);
+        let b = make_session("b", 2);
+        let mut state = UiState::new(vec![a.clone(), b.clone()]);
+        state.sessions[1].summary = Some("parsed".to_string());
+
+        let result = state.merge_sessions(vec![a, b]);
+
+        assert!(result.stale.is_empty());
+        assert_eq!(result.added, 0);
+        assert_eq!(result.removed, 0);
+        assert_eq!(state.sessions[1].summary.as_deref(), Some("parsed"));
+    }
+
+    #[test]
+    fn test_merge_sessions_follows_renamed_project_dir() {
+        let a = make_session("a", 1);
+        let mut state = UiState::new(vec![a.clone()]);
+        state.sessions[0].summary = Some("parsed".to_string());
+
+        let mut moved = a.clone();
+        moved.path = PathBuf::from("/tmp/renamed/a.jsonl");
+        moved.project = "renamed".to_string();
+        let result = state.merge_sessions(vec![moved]);
+
+        assert!(result.stale.is_empty());
+        assert_eq!((result.added, result.removed), (0, 0));
+        assert_eq!(state.sessions[0].summary.as_deref(), Some("parsed"));
+        assert_eq!(state.sessions[0].project, "renamed");
+        assert_eq!(state.sessions[0].path, PathBuf::from("/tmp/renamed/a.jsonl"));
+    }
+
+    #[test]
+    fn test_merge_sessions_tracks_changes_and_preserves_cursor() {
+        let a = make_session("a", 1);
+        let b = make_session("b", 2);
+        let c = make_session("c", 3);
+        let mut state = UiState::new(vec![a.clone(), b.clone(), c.clone()]);
+        state.cursor = 2; // "c"
+        state.selected.insert(1); // "b"
+
+        let mut changed_a = a.clone();
+        changed_a.size_bytes = 200;
+        let d = make_session("d", 0);
+        let result = state.merge_sessions(vec![d, changed_a, c]);
+
+        assert_eq!(result.added, 1);
+        assert_eq!(result.removed, 1);
+        assert_eq!(result.stale, vec![0, 1]);
+        assert_eq!(state.current_session().unwrap().id, "c");
+        assert!(state.selected.is_empty());
+    }
+
+    #[test]
+    fn test_active_session_floats_above_pins() {
+        let mut agent = make_session("agent", 0);
+        agent.is_agent = true;
+        agent.size_bytes = 1;
+        let mut newest = make_session("newest", 1);
+        newest.size_bytes = 10;
+        let mut big = make_session("big", 60);
+        big.size_bytes = 1000;
+        let mut pinned = make_session("pinned", 120);
+        pinned.size_bytes = 5;
+        let mut state = UiState::new(vec![agent, newest, big, pinned]);
+        state.filter.include_agents = true;
+        state.pinned.insert("pinned".to_string());
+        state.sort_field = SortField::Size;
+
+        state.toggle_float_active();
+        assert_eq!(state.active_session, Some(1));
+        assert_eq!(state.filtered_indices, vec![1, 3, 2, 0]);
+
+        state.toggle_float_active();
+        assert_eq!(state.active_session, None);
+        assert_eq!(state.filtered_indices, vec![3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn test_created_sort_ignores_modification_time() {
+        // Started long ago but touched recently
+        let mut resumed = make_session("resumed", 1);
+        resumed.created = Some(Utc::now() - Duration::days(30));
+        let mut fresh = make_session("fresh", 60);
+        fresh.created = Some(Utc::now() - Duration::hours(2));
+        // No timestamps: falls back to the file time
+        let unparsed = make_session("unparsed", 60 * 24 * 3);
+        let mut state = UiState::new(vec![resumed, fresh, unparsed]);
+
+        state.sort_field = SortField::Created;
+        state.apply_sort();
+        assert_eq!(state.filtered_indices, vec![1, 2, 0]);
+
+        state.sort_field = SortField::Date;
+        state.apply_sort();
+        assert_eq!(state.filtered_indices, vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_sort_ties_break_on_project_then_date() {
+        let session = |id: &str, project: &str, size: u64, hours: i64| {
+            let mut s = make_session(id, hours * 60);
+            s.project = project.to_string();
+            s.size_bytes = size;
+            s
+        };
+        let mut state = UiState::new(vec![
+            session("old-b", "b", 10, 5),
+            session("new-b", "b", 10, 1),
+            session("a", "a", 10, 9),
+            session("big", "z", 99, 9),
+        ]);
+
+        state.sort_field = SortField::Size;
+        state.apply_sort();
+        let ids = |state: &UiState| -> Vec<String> {
+            state
+                .filtered_indices
+                .iter()
+                .map(|&i| state.sessions[i].id.clone())
+                .collect()
+        };
+        assert_eq!(ids(&state), vec!["big", "a", "new-b", "old-b"]);
+
+        // Reversing flips sizes but ties stay by project, newest first
+        state.sort_reversed = true;
+        state.apply_sort();
+        assert_eq!(ids(&state), vec!["a", "new-b", "old-b", "big"]);
+
+        state.sort_field = SortField::Project;
+        state.sort_reversed = false;
+        state.apply_sort();
+        assert_eq!(ids(&state), vec!["a", "new-b", "old-b", "big"]);
+    }
+
+    #[test]
+    fn test_help_returns_to_opening_view() {
+        let mut state = UiState::new(Vec::new());
+        state.view = View::Preview;
+        state.open_help();
+        assert_eq!(state.view, View::Help);
+        assert_eq!(state.help_context, View::Preview);
+        state.close_help();
+        assert_eq!(state.view, View::Preview);
+    }
+}
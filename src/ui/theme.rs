@@ -0,0 +1,138 @@
+use ratatui::style::Color;
+
+/// Built-in UI color presets (cycled with `T` in the list)
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum, serde::Serialize, serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorTheme {
+    #[default]
+    Default,
+    /// Bright foregrounds and solid highlight backgrounds
+    HighContrast,
+    /// Shades of gray only, for terminals or eyes that don't separate hues well
+    Monochrome,
+}
+
+impl ColorTheme {
+    pub fn next(self) -> Self {
+        match self {
+            ColorTheme::Default => ColorTheme::HighContrast,
+            ColorTheme::HighContrast => ColorTheme::Monochrome,
+            ColorTheme::Monochrome => ColorTheme::Default,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorTheme::Default => "default",
+            ColorTheme::HighContrast => "high-contrast",
+            ColorTheme::Monochrome => "monochrome",
+        }
+    }
+
+    /// Colors for this preset
+    pub fn theme(self) -> Theme {
+        match self {
+            ColorTheme::Default => Theme {
+                accent: Color::Cyan,
+                highlight: Color::Yellow,
+                positive: Color::Green,
+                negative: Color::Red,
+                sort: Color::Magenta,
+                muted: Color::DarkGray,
+                user: Color::Green,
+                assistant: Color::Blue,
+                system: Color::Yellow,
+                tool_result: Color::Gray,
+                cursor_bg: Color::DarkGray,
+                line_cursor_bg: Color::Rgb(60, 60, 60),
+                line_selected_bg: Color::Rgb(50, 50, 80),
+                code_bg: Color::Rgb(30, 30, 46),
+                popup_bg: Color::Black,
+            },
+            ColorTheme::HighContrast => Theme {
+                accent: Color::LightCyan,
+                highlight: Color::LightYellow,
+                positive: Color::LightGreen,
+                negative: Color::LightRed,
+                sort: Color::LightMagenta,
+                muted: Color::Gray,
+                user: Color::LightGreen,
+                assistant: Color::LightBlue,
+                system: Color::LightYellow,
+                tool_result: Color::White,
+                cursor_bg: Color::Blue,
+                line_cursor_bg: Color::Blue,
+                line_selected_bg: Color::Magenta,
+                code_bg: Color::Black,
+                popup_bg: Color::Black,
+            },
+            ColorTheme::Monochrome => Theme {
+                accent: Color::White,
+                highlight: Color::White,
+                positive: Color::White,
+                negative: Color::White,
+                sort: Color::White,
+                muted: Color::DarkGray,
+                user: Color::White,
+                assistant: Color::Gray,
+                system: Color::Gray,
+                tool_result: Color::Gray,
+                cursor_bg: Color::DarkGray,
+                line_cursor_bg: Color::DarkGray,
+                line_selected_bg: Color::Rgb(40, 40, 40),
+                code_bg: Color::Reset,
+                popup_bg: Color::Black,
+            },
+        }
+    }
+}
+
+/// Role and accent colors used to draw the TUI
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Theme {
+    /// Key hints, date/size filter tags, tool calls, progress bars
+    pub accent: Color,
+    /// Project filter, selected rows, search bars, search matches
+    pub highlight: Color,
+    /// Status messages, active filters, recent sessions, added diff lines
+    pub positive: Color,
+    /// Removed diff lines
+    pub negative: Color,
+    /// Sort indicator and code fences
+    pub sort: Color,
+    /// Secondary text: hints, totals, thinking, empty sessions
+    pub muted: Color,
+    pub user: Color,
+    pub assistant: Color,
+    pub system: Color,
+    pub tool_result: Color,
+    /// Cursor row in the session table and pickers
+    pub cursor_bg: Color,
+    /// Cursor line in the preview
+    pub line_cursor_bg: Color,
+    /// Selected messages in the preview
+    pub line_selected_bg: Color,
+    /// Fenced code blocks in the preview
+    pub code_bg: Color,
+    /// Dialogs, help and pickers
+    pub popup_bg: Color,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_color_theme_cycles_through_presets() {
+        let mut theme = ColorTheme::default();
+        let mut seen = Vec::new();
+        for _ in 0..3 {
+            seen.push(theme.as_str());
+            theme = theme.next();
+        }
+        assert_eq!(theme, ColorTheme::Default);
+        assert_eq!(seen, vec!["default", "high-contrast", "monochrome"]);
+    }
+}
@@ -0,0 +1,527 @@
+use chrono::{DateTime, Duration, Utc};
+use std::ops::Range;
+use std::path::PathBuf;
+
+/// When to color non-TUI output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorMode {
+    /// Color when stdout is a terminal and NO_COLOR is unset
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorMode {
+    /// Resolve to on/off; `auto` needs a terminal and an unset or empty `NO_COLOR`
+    pub fn enabled(self, is_terminal: bool, no_color_env: bool) -> bool {
+        match self {
+            ColorMode::Always => true,
+            ColorMode::Never => false,
+            ColorMode::Auto => is_terminal && !no_color_env,
+        }
+    }
+}
+
+/// ANSI styling for CLI output; every method returns the text unchanged when disabled
+///
+/// Pad text before painting it, since escape codes count towards `{:<N}` widths.
+#[derive(Debug, Clone, Copy)]
+pub struct Colors {
+    enabled: bool,
+}
+
+impl Colors {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled }
+    }
+
+    pub fn bold(self, text: &str) -> String {
+        self.paint("1", text)
+    }
+
+    pub fn dim(self, text: &str) -> String {
+        self.paint("2", text)
+    }
+
+    pub fn yellow(self, text: &str) -> String {
+        self.paint("33", text)
+    }
+
+    fn paint(self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("\x1b[{}m{}\x1b[0m", code, text)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Format token count with K/M suffix
+pub fn format_tokens(tokens: usize) -> String {
+    if tokens >= 1_000_000 {
+        format!("{:.1}M", tokens as f64 / 1_000_000.0)
+    } else if tokens >= 1_000 {
+        format!("{:.1}K", tokens as f64 / 1_000.0)
+    } else {
+        tokens.to_string()
+    }
+}
+
+/// Format a timestamp relative to `now` ("just now", "3h ago", "2d ago")
+/// Returns None for anything older than a week so callers can fall back to an absolute date
+pub fn format_relative_time(time: DateTime<Utc>, now: DateTime<Utc>) -> Option<String> {
+    let elapsed = now.signed_duration_since(time);
+
+    if elapsed.num_days() >= 7 {
+        None
+    } else if elapsed.num_minutes() >= 1 {
+        Some(format!("{} ago", format_age(time, now)))
+    } else {
+        Some("just now".to_string())
+    }
+}
+
+/// Compact age of a timestamp ("now", "5m", "3h", "3d", "2w", "5mo", "1y")
+pub fn format_age(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let elapsed = now.signed_duration_since(time);
+    let days = elapsed.num_days();
+
+    if days >= 365 {
+        format!("{}y", days / 365)
+    } else if days >= 30 {
+        format!("{}mo", days / 30)
+    } else if days >= 7 {
+        format!("{}w", days / 7)
+    } else if days >= 1 {
+        format!("{}d", days)
+    } else if elapsed.num_hours() >= 1 {
+        format!("{}h", elapsed.num_hours())
+    } else if elapsed.num_minutes() >= 1 {
+        format!("{}m", elapsed.num_minutes())
+    } else {
+        "now".to_string()
+    }
+}
+
+/// Format a span of time with its two largest units ("2d 3h", "1h 20m", "45s")
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.num_seconds().max(0);
+    let (days, hours, minutes) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Extract a single-line snippet around the first occurrence of `query` in `content`
+/// `query` must already be lowercased to match the lowercased search content
+pub fn find_snippet(content: &str, query: &str, context_chars: usize) -> Option<String> {
+    if query.is_empty() {
+        return None;
+    }
+    let byte_idx = content.find(query)?;
+
+    let start_char = content[..byte_idx].chars().count();
+    let query_chars = query.chars().count();
+    let skip = start_char.saturating_sub(context_chars);
+
+    let snippet: String = content
+        .chars()
+        .skip(skip)
+        .take(start_char - skip + query_chars + context_chars)
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+
+    let prefix = if skip > 0 { "..." } else { "" };
+    let suffix = if skip + snippet.chars().count() < content.chars().count() {
+        "..."
+    } else {
+        ""
+    };
+    Some(format!("{}{}{}", prefix, snippet.trim(), suffix))
+}
+
+/// Make a project name usable as part of a file name (disambiguated names contain `/`)
+pub fn file_safe(name: &str) -> String {
+    name.trim_start_matches(['/', '\\']).replace(['/', '\\'], "-")
+}
+
+/// Make arbitrary text usable as a single file name component
+///
+/// Path separators, characters Windows rejects and control characters become `-`;
+/// surrounding whitespace and dots are trimmed.
+pub fn sanitize_file_component(name: &str) -> String {
+    name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '-',
+            c if c.is_control() => '-',
+            c => c,
+        })
+        .collect::<String>()
+        .trim_matches(|c: char| c.is_whitespace() || c == '.')
+        .to_string()
+}
+
+/// Locate an executable on `$PATH` (names are used as given, so include `.exe` on Windows)
+pub fn find_in_path(program: &str) -> Option<PathBuf> {
+    let paths = std::env::var_os("PATH")?;
+    std::env::split_paths(&paths)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+/// Markers used by the TUI and rendered content, so `--ascii` swaps them in one place
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Symbols {
+    pub selected: &'static str,
+    pub pinned: &'static str,
+    pub active: &'static str,
+    pub agent: &'static str,
+    pub sidecar: &'static str,
+    pub warning: &'static str,
+    pub sort_up: &'static str,
+    pub sort_down: &'static str,
+    pub left_right: &'static str,
+    /// Text cursor in search prompts
+    pub caret: &'static str,
+    pub separator: &'static str,
+    pub approx: &'static str,
+    pub at_least: &'static str,
+    pub at_most: &'static str,
+    pub tool: &'static str,
+    pub thinking: &'static str,
+    pub result: &'static str,
+    pub spinner: &'static [char],
+}
+
+impl Symbols {
+    pub const UNICODE: Symbols = Symbols {
+        selected: "●",
+        pinned: "★",
+        active: "▶",
+        agent: "⚙",
+        sidecar: "📁",
+        warning: "⚠",
+        sort_up: "↑",
+        sort_down: "↓",
+        left_right: "←/→",
+        caret: "▏",
+        separator: "·",
+        approx: "≈",
+        at_least: "≥",
+        at_most: "≤",
+        tool: "🔧",
+        thinking: "💭",
+        result: "📋",
+        spinner: &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'],
+    };
+
+    pub const ASCII: Symbols = Symbols {
+        selected: "*",
+        pinned: "+",
+        active: ">",
+        agent: "@",
+        sidecar: "[dir]",
+        warning: "!",
+        sort_up: "^",
+        sort_down: "v",
+        left_right: "Left/Right",
+        caret: "_",
+        separator: "|",
+        approx: "~",
+        at_least: ">=",
+        at_most: "<=",
+        tool: "[tool]",
+        thinking: "[think]",
+        result: "[result]",
+        spinner: &['|', '/', '-', '\\'],
+    };
+
+    pub fn new(ascii: bool) -> Self {
+        if ascii {
+            Self::ASCII
+        } else {
+            Self::UNICODE
+        }
+    }
+
+    /// Arrow for the sort direction
+    pub fn sort_arrow(&self, reversed: bool) -> &'static str {
+        if reversed {
+            self.sort_up
+        } else {
+            self.sort_down
+        }
+    }
+}
+
+/// Format a timestamp in UTC or, with `local`, in the system time zone
+///
+/// `%Z` prints "UTC" or the local offset (e.g. "+02:00").
+pub fn format_time(time: DateTime<Utc>, fmt: &str, local: bool) -> String {
+    if local {
+        time.with_timezone(&chrono::Local).format(fmt).to_string()
+    } else {
+        time.format(fmt).to_string()
+    }
+}
+
+/// Parse a human size such as `500K`, `1.5M`, `2GiB` or `1024` into bytes
+///
+/// Units are binary (K = 1024), matching how sizes are displayed; case and a trailing
+/// `B`/`iB` are ignored.
+pub fn parse_size(text: &str) -> Option<u64> {
+    let text = text.trim();
+    let upper = text.to_ascii_uppercase();
+    let number = upper
+        .strip_suffix("IB")
+        .or_else(|| upper.strip_suffix('B'))
+        .unwrap_or(&upper);
+    let (number, multiplier) = match number.char_indices().last()? {
+        (i, 'K') => (&number[..i], 1u64 << 10),
+        (i, 'M') => (&number[..i], 1 << 20),
+        (i, 'G') => (&number[..i], 1 << 30),
+        (i, 'T') => (&number[..i], 1 << 40),
+        _ => (number, 1),
+    };
+    let value: f64 = number.trim().parse().ok()?;
+    (value.is_finite() && value >= 0.0).then(|| (value * multiplier as f64).round() as u64)
+}
+
+/// Remove ANSI escape sequences (colors, cursor movement, OSC titles) from terminal output
+///
+/// A sequence cut off at the end of the text is dropped too.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters and intermediates, then a final byte in @..~
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            // OSC: runs until BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == '\x1b' && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            // Two-character sequences such as ESC ( B
+            Some('(' | ')') => {
+                chars.next();
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+/// Byte ranges in `text` of every non-overlapping case-insensitive match of `query`
+/// `query` must already be lowercased
+pub fn find_match_ranges(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    // Lowercasing can change byte lengths, so map each lowered byte back to its source char
+    let mut lowered = String::with_capacity(text.len());
+    let mut origin: Vec<(usize, usize)> = Vec::with_capacity(text.len());
+    for (idx, c) in text.char_indices() {
+        for lc in c.to_lowercase() {
+            lowered.push(lc);
+            for _ in 0..lc.len_utf8() {
+                origin.push((idx, idx + c.len_utf8()));
+            }
+        }
+    }
+
+    let mut ranges: Vec<Range<usize>> = Vec::new();
+    for (start, matched) in lowered.match_indices(query) {
+        let begin = origin[start].0;
+        let end = origin[start + matched.len() - 1].1;
+        match ranges.last_mut() {
+            // One source char can lower to several; don't emit overlapping ranges
+            Some(last) if begin < last.end => last.end = last.end.max(end),
+            _ => ranges.push(begin..end),
+        }
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_format_relative_time() {
+        let now = Utc::now();
+        assert_eq!(format_relative_time(now, now).as_deref(), Some("just now"));
+        assert_eq!(
+            format_relative_time(now - Duration::minutes(5), now).as_deref(),
+            Some("5m ago")
+        );
+        assert_eq!(
+            format_relative_time(now - Duration::hours(3), now).as_deref(),
+            Some("3h ago")
+        );
+        assert_eq!(
+            format_relative_time(now - Duration::days(2), now).as_deref(),
+            Some("2d ago")
+        );
+    }
+
+    #[test]
+    fn test_find_snippet() {
+        let content = "the quick brown fox jumps over the lazy dog";
+        assert_eq!(find_snippet(content, "fox", 6).as_deref(), Some("...brown fox jumps..."));
+        assert_eq!(find_snippet(content, "the", 4).as_deref(), Some("the qui..."));
+        assert_eq!(find_snippet(content, "cat", 4), None);
+    }
+
+    #[test]
+    fn test_find_snippet_multibyte() {
+        let content = "héllo wörld ünïcode";
+        assert_eq!(find_snippet(content, "wörld", 2).as_deref(), Some("...o wörld ü..."));
+    }
+
+    #[test]
+    fn test_sanitize_file_component() {
+        assert_eq!(sanitize_file_component("a/b:c*?"), "a-b-c--");
+        assert_eq!(sanitize_file_component("  ..name. "), "name");
+        assert_eq!(sanitize_file_component("line\nbreak"), "line-break");
+    }
+
+    #[test]
+    fn test_color_mode_enabled() {
+        assert!(ColorMode::Auto.enabled(true, false));
+        assert!(!ColorMode::Auto.enabled(true, true));
+        assert!(!ColorMode::Auto.enabled(false, false));
+        assert!(ColorMode::Always.enabled(false, true));
+        assert!(!ColorMode::Never.enabled(true, false));
+    }
+
+    #[test]
+    fn test_colors_disabled_is_plain() {
+        assert_eq!(Colors::new(false).bold("x"), "x");
+        assert_eq!(Colors::new(true).dim("x"), "\x1b[2mx\x1b[0m");
+    }
+
+    #[test]
+    fn test_format_time() {
+        let time = DateTime::parse_from_rfc3339("2026-03-01T12:30:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(format_time(time, "%Y-%m-%d %H:%M %Z", false), "2026-03-01 12:30 UTC");
+        let local = time.with_timezone(&chrono::Local);
+        assert_eq!(format_time(time, "%H:%M", true), local.format("%H:%M").to_string());
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1024"), Some(1024));
+        assert_eq!(parse_size("500K"), Some(500 * 1024));
+        assert_eq!(parse_size("1m"), Some(1 << 20));
+        assert_eq!(parse_size("1.5 MiB"), Some(3 << 19));
+        assert_eq!(parse_size("2GB"), Some(2 << 30));
+        assert_eq!(parse_size("0B"), Some(0));
+        assert_eq!(parse_size("M"), None);
+        assert_eq!(parse_size("-1K"), None);
+        assert_eq!(parse_size("ten"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[31merror\x1b[0m: bad"), "error: bad");
+        assert_eq!(strip_ansi("\x1b[1;38;5;208mbold\x1b[m"), "bold");
+        assert_eq!(strip_ansi("\x1b]0;title\x07done"), "done");
+        assert_eq!(strip_ansi("\x1b(Bplain ✓"), "plain ✓");
+        assert_eq!(strip_ansi("cut \x1b[3"), "cut ");
+    }
+
+    #[test]
+    fn test_file_safe() {
+        assert_eq!(file_safe("my-app"), "my-app");
+        assert_eq!(file_safe("a/foo"), "a-foo");
+        assert_eq!(file_safe("/home/a/foo"), "home-a-foo");
+    }
+
+    #[test]
+    fn test_find_match_ranges() {
+        assert_eq!(find_match_ranges("Foo bar foo", "foo"), vec![0..3, 8..11]);
+        assert_eq!(find_match_ranges("aaaa", "aa"), vec![0..2, 2..4]);
+        assert!(find_match_ranges("abc", "").is_empty());
+    }
+
+    #[test]
+    fn test_find_match_ranges_multibyte() {
+        // 'İ' lowercases to two chars; offsets must still point into the original
+        let text = "xİy Wörld";
+        assert_eq!(find_match_ranges(text, "wö"), vec![5..8]);
+        assert_eq!(&text[5..8], "Wö");
+    }
+
+    #[test]
+    fn test_format_age() {
+        let now = Utc::now();
+        assert_eq!(format_age(now, now), "now");
+        assert_eq!(format_age(now - Duration::hours(5), now), "5h");
+        assert_eq!(format_age(now - Duration::days(3), now), "3d");
+        assert_eq!(format_age(now - Duration::days(15), now), "2w");
+        assert_eq!(format_age(now - Duration::days(160), now), "5mo");
+        assert_eq!(format_age(now - Duration::days(400), now), "1y");
+    }
+
+    #[test]
+    fn test_ascii_symbols_are_ascii() {
+        let s = Symbols::ASCII;
+        let texts = [
+            s.selected, s.pinned, s.active, s.agent, s.sidecar, s.warning, s.sort_up, s.sort_down,
+            s.left_right, s.caret, s.separator, s.approx, s.at_least, s.at_most, s.tool,
+            s.thinking, s.result,
+        ];
+        assert!(texts.iter().all(|t| t.is_ascii()));
+        assert!(s.spinner.iter().all(char::is_ascii));
+    }
+
+    #[test]
+    fn test_find_in_path_skips_missing_programs() {
+        assert!(find_in_path("ccsessionctl-no-such-program").is_none());
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::seconds(45)), "45s");
+        assert_eq!(format_duration(Duration::minutes(20)), "20m");
+        assert_eq!(format_duration(Duration::minutes(80)), "1h 20m");
+        assert_eq!(format_duration(Duration::hours(51)), "2d 3h");
+    }
+
+    #[test]
+    fn test_format_relative_time_falls_back_after_a_week() {
+        let now = Utc::now();
+        assert_eq!(format_relative_time(now - Duration::days(8), now), None);
+    }
+}